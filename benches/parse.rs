@@ -0,0 +1,132 @@
+//! Parsing and rendering of a generated song library. Run with
+//! `cargo bench --bench parse`. To compare two revisions, run
+//! `cargo bench --bench parse -- --save-baseline old` on the first one and
+//! `cargo bench --bench parse -- --baseline old` on the second one.
+//! Revisions from before the bench existed need the `criterion`
+//! dev-dependency, the `[[bench]]` entry and this file copied in.
+//!
+//! Lexing from the whole string with interned identifiers, compared with
+//! the previous iterator based lexer (median of criterion, same machine):
+//!
+//! ```text
+//!                 before     after
+//! parse           47.2 ms    33.3 ms
+//! render-text     633 µs     163 µs
+//! render-latex    46.3 ms    39.1 ms
+//! ```
+
+use std::{fmt::Write, hint::black_box, io, time::Duration};
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use wohu::{out_fmt, parse_str, render};
+
+/// Number of songs in the generated library.
+const SONGS: usize = 1500;
+
+const WORDS: &[&str] = &[
+    "Hospodin",
+    "je",
+    "můj",
+    "pastýř",
+    "nic",
+    "mi",
+    "nechybí",
+    "v",
+    "zeleném",
+    "údolí",
+    "odpočívám",
+    "a",
+    "k",
+    "vodám",
+    "klidným",
+    "vede",
+    "mě",
+    "the",
+    "Lord",
+    "is",
+    "my",
+    "shepherd",
+    "I",
+    "shall",
+    "not",
+    "want",
+];
+
+/// Song with two languages, verses shared by both of them and a chorus
+/// that repeats.
+fn song(seed: usize) -> String {
+    let mut word = seed;
+    let mut line = |res: &mut String| {
+        for i in 0..6 {
+            word = (word * 31 + 7) % WORDS.len();
+            if i != 0 {
+                res.push(' ');
+            }
+            res.push_str(WORDS[word]);
+        }
+        res.push('\n');
+    };
+
+    let mut res = String::new();
+    writeln!(res, "// Song {seed}").unwrap();
+    writeln!(
+        res,
+        "name = {{ cs: \"Píseň {seed}\", en: \"Song {seed}\" }}"
+    )
+    .unwrap();
+    for v in 1..=4 {
+        writeln!(res, "v{v}: l{{").unwrap();
+        for _ in 0..4 {
+            res.push_str("    ");
+            line(&mut res);
+        }
+        res.push_str("}\n");
+    }
+    res.push_str("order = [v1, c, v2, c, v3, c, v4, c, c]\n");
+    for lang in ["cs", "en"] {
+        writeln!(res, "#language {lang}\nc: l{{").unwrap();
+        for _ in 0..4 {
+            res.push_str("    ");
+            line(&mut res);
+        }
+        res.push_str("} + v1\n");
+    }
+    res
+}
+
+fn library() -> Vec<String> {
+    (0..SONGS).map(song).collect()
+}
+
+fn bench(c: &mut Criterion) {
+    let lib = library();
+    let bytes = lib.iter().map(|s| s.len() as u64).sum();
+    let songs: Vec<_> = lib.iter().map(|s| parse_str(s).unwrap()).collect();
+
+    let mut group = c.benchmark_group("library");
+    group
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(5))
+        .throughput(Throughput::Bytes(bytes));
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for s in &lib {
+                black_box(parse_str(black_box(s)).unwrap());
+            }
+        })
+    });
+    group.bench_function("render-text", |b| {
+        b.iter(|| {
+            render(&songs, &mut [out_fmt::Text::new(io::sink())]).unwrap()
+        })
+    });
+    group.bench_function("render-latex", |b| {
+        b.iter(|| {
+            render(&songs, &mut [out_fmt::Latex::new(io::sink())]).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -1,46 +1,93 @@
-use std::path::PathBuf;
-
 use anyhow::Result;
 use pareg::Pareg;
 
-use crate::out_fmt::FmtType;
+use crate::cli::{
+    BuildArgs, CheckArgs, Command, CompletionsArgs, ConfigArgs, ConfigSource,
+    DisplayArgs, FmtArgs, ImportArgs, ListArgs, LspArgs, PresentArgs,
+    ServeArgs, help,
+};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Args {
-    pub input: Vec<String>,
-    pub outputs: Vec<(FmtType, Option<PathBuf>)>,
+    pub command: Command,
 }
 
 impl Args {
+    /// Parse the arguments. Values from the configuration are used as
+    /// defaults. The configuration is loaded only for commands that use it,
+    /// so that invalid configuration doesn't break the other commands.
     pub fn parse(mut args: Pareg) -> Result<Self> {
-        let mut res = Self::default();
-        let mut output = None;
-
-        while let Some(a) = args.next() {
-            match a {
-                "-o" | "--output" => output = Some(args.next_arg()?),
-                "--stdout" => output = None,
-                "-i" | "--input" => res.input.push(args.next_arg()?),
-                "-f" | "--fmt" | "--format" => {
-                    res.outputs.push((args.next_arg()?, output.clone()))
+        let mut source = ConfigSource::Discover;
+        loop {
+            match args.peek() {
+                Some("--config") => {
+                    args.next();
+                    source = ConfigSource::File(args.next_arg()?);
                 }
-                "--text" => res.outputs.push((FmtType::Text, output.clone())),
-                "--tex-slides" | "--latex-slides" => {
-                    res.outputs.push((FmtType::LatexSlides, output.clone()))
+                Some("--no-config") => {
+                    args.next();
+                    source = ConfigSource::Disabled;
                 }
-                v if v.starts_with('-') => {
-                    return Err(args
-                        .err_unknown_argument()
-                        .hint(
-                            "Use `-i` to specify input file that starts \
-                                with `-`.",
-                        )
-                        .into());
-                }
-                v => res.input.push(v.to_string()),
+                _ => break,
             }
         }
 
-        Ok(res)
+        let command = match args.peek() {
+            Some("-h" | "--help") => Command::Help(None),
+            Some("-V" | "--version") => Command::Version,
+            Some("help") => {
+                args.next();
+                let cmd = args.next().and_then(help::find_command);
+                Command::Help(cmd.map(|c| c.name))
+            }
+            Some("build") => {
+                args.next();
+                BuildArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("check") => {
+                args.next();
+                CheckArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("fmt") => {
+                args.next();
+                FmtArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("list") => {
+                args.next();
+                ListArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("import") => {
+                args.next();
+                ImportArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("present") => {
+                args.next();
+                PresentArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("serve") => {
+                args.next();
+                ServeArgs::parse(&mut args, &source.load()?)?
+            }
+            Some("display") => {
+                args.next();
+                DisplayArgs::parse(&mut args)?
+            }
+            Some("lsp") => {
+                args.next();
+                LspArgs::parse(&mut args, source)?
+            }
+            Some("config") => {
+                args.next();
+                ConfigArgs::parse(&mut args, source.load()?)?
+            }
+            Some("completions") => {
+                args.next();
+                CompletionsArgs::parse(&mut args)?
+            }
+            // Without subcommand, build is the default.
+            _ => BuildArgs::parse(&mut args, &source.load()?)?,
+        };
+
+        Ok(Self { command })
     }
 }
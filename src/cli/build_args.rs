@@ -0,0 +1,278 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::{encoding::InputEncoding, out_fmt::FmtType};
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, STDIN, ValueKind};
+
+#[derive(Debug, Default)]
+pub struct BuildArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+    pub outputs: Vec<BuildTarget>,
+    pub keep_partial: bool,
+    pub watch: bool,
+    /// Default style of `html-overlay`.
+    pub theme: Vec<(String, String)>,
+    /// Configuration file that the arguments were loaded from.
+    pub config: Option<PathBuf>,
+    /// Library from the configuration, see [`Config::library`].
+    pub library: Option<PathBuf>,
+}
+
+/// Single output of `build` together with its options.
+#[derive(Debug, Clone)]
+pub struct BuildTarget {
+    pub fmt: FmtType,
+    /// The output is written to stdout if there is no path.
+    pub path: Option<PathBuf>,
+    /// Languages in which the songs are rendered. Empty for the default
+    /// language.
+    pub languages: Vec<String>,
+    pub notes: bool,
+    pub smart_quotes: bool,
+}
+
+impl BuildArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "build",
+        usage: "[options] [targets] [inputs]",
+        about: "Render songs to the selected outputs. This is the default.",
+        description: "Render the input songs to each of the selected \
+            outputs. Songs are rendered in the order in which they are \
+            given. All the inputs are parsed even if some of them fail, so \
+            that errors in all of them are reported. Inputs are parsed and \
+            output files are rendered in parallel, but the outputs and the \
+            errors are always in the order of the inputs.",
+        options: &[
+            OptSpec::path(
+                &["-o", "--output"],
+                "path",
+                "Write the following outputs to the file.",
+            ),
+            OptSpec::flag(
+                &["--stdout"],
+                "Write the following outputs to stdout.",
+            ),
+            OptSpec::choice(
+                &["-f", "--fmt", "--format"],
+                "format",
+                &FmtType::NAMES,
+                "Add output with the given format.",
+            ),
+            OptSpec::flag(&["--text"], "Same as `-f text`."),
+            OptSpec::flag(
+                &["--tex-slides", "--latex-slides"],
+                "Same as `-f latex-slides`.",
+            ),
+            OptSpec::flag(
+                &["--tex-stage", "--latex-stage"],
+                "Same as `-f latex-stage`.",
+            ),
+            OptSpec::any(
+                &["-l", "--language"],
+                "lang",
+                "Render the songs in the language. May be given multiple \
+                times.",
+            ),
+            OptSpec::flag(
+                &["--notes"],
+                "Include the notes for the operator in text outputs.",
+            ),
+            OptSpec::flag(
+                &["--smart-quotes"],
+                "Use typographic quotes, dashes and ellipses of the song \
+                language.",
+            ),
+            OptSpec::flag(
+                &["--keep-partial"],
+                "Write partial output files even if rendering fails.",
+            ),
+            OptSpec::flag(
+                &["--watch"],
+                "Rebuild the outputs whenever any of the inputs changes.",
+            ),
+            OptSpec::ENCODING,
+            OptSpec::STDIN,
+            OptSpec::INPUT,
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[
+            (
+                "Outputs",
+                "Each format option (`-f`, `--text`, `--tex-slides`, \
+                `--tex-stage`) adds \
+                one output. The output is written to the file given by the \
+                last `-o` before the format option, or to stdout if there \
+                is no such `-o` or if `--stdout` follows it. So `-o a.tex \
+                --tex-slides --stdout --text` writes slides to `a.tex` and \
+                text to stdout. Each file should be used by only one \
+                output. Without any format option, the outputs from \
+                `wohu.toml` are used, see `wohu help config`. \
+                Files are written to temporary files first and they replace \
+                the old files only after all the inputs are rendered \
+                successfully.",
+            ),
+            (
+                "Targets",
+                "Named targets are defined in `wohu.toml` as tables \
+                `[targets.<name>]`, each with its `format`, optional `path`, \
+                `languages`, `notes` and `smart-quotes`. Positional argument \
+                that is name of a target selects the target, use `-i` for \
+                input with the same name. Without any target or format \
+                option, all the targets are built. The inputs are parsed \
+                only once for all the targets. Options on the command line \
+                apply to all the built outputs, `-l` replaces the languages \
+                of the targets.",
+            ),
+            (
+                "Languages",
+                "Songs are rendered in their default language. With `-l` or \
+                `languages`, each song is rendered in each of the selected \
+                languages that it has, e.g. `-l cs -l en` gives bilingual \
+                output. Songs without any of the languages are rendered in \
+                their default language. Outputs and targets without \
+                languages use `language` from `wohu.toml`.",
+            ),
+            (
+                "Notes",
+                "Notes for the operator are never shown to the audience. \
+                `latex-slides` has them as beamer notes that are hidden \
+                unless `\\setbeameroption{show notes}` is added, \
+                `latex-stage` shows them on the slides and `text` includes \
+                them only with `--notes`. Because of notes, `note` is \
+                keyword and songs that use it as verse identifier must \
+                rename the verse.",
+            ),
+            (
+                "Typography",
+                "LaTeX and HTML outputs follow the typography of the song \
+                language given by `#language`. For Czech (`cs`) and Slovak \
+                (`sk`) single-letter prepositions and conjunctions are \
+                joined with the next word by non-breaking space. With \
+                `--smart-quotes`, quotes, apostrophes, dashes between words \
+                and `...` are also converted to the typographic forms of the \
+                language in all outputs, e.g. `\"a\"` becomes `„a“` in \
+                Czech. Use `wohu check -W typography` to report them instead.",
+            ),
+            (
+                "Overlay style",
+                "The style of `html-overlay` may be changed with query \
+                parameters in the URL, e.g. `overlay.html?size=60&lines=1`. \
+                The parameters are `font`, `size` (px), `color`, `outline` \
+                (px), `outline-color`, `shadow` (px), `shadow-color`, \
+                `bottom` (% of height) and `lines` (lines shown at once, \
+                default 2). The same parameters work for the live overlay \
+                of `wohu serve`. Their defaults may be set in the `[theme]` \
+                table of `wohu.toml`.",
+            ),
+            (
+                "Watch mode",
+                "With `--watch`, wohu doesn't exit after the build. It \
+                checks the inputs for changes and when any of them changes, \
+                all the outputs are built again. `wohu.toml` is loaded again \
+                before each build and inputs that are added to the library \
+                later are found. Errors are reported and the watching \
+                continues. Stop it with Ctrl+C.",
+            ),
+            (
+                "Exit codes",
+                "0  All outputs were written successfully.\n\
+                1  Failed to read, parse or render the inputs.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            keep_partial: conf.keep_partial,
+            theme: conf.theme.clone(),
+            config: conf.path.clone(),
+            library: conf.library.clone(),
+            ..Self::default()
+        };
+        let mut output = None;
+        let mut outputs = vec![];
+        let mut targets = vec![];
+        let mut languages = vec![];
+        let mut notes = false;
+        let mut smart_quotes = false;
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "-o" | "--output" => output = Some(args.next_arg()?),
+                "--stdout" => output = None,
+                "--keep-partial" => res.keep_partial = true,
+                "-l" | "--language" => languages.push(args.next_arg()?),
+                "--notes" => notes = true,
+                "--smart-quotes" => smart_quotes = true,
+                "--watch" => res.watch = true,
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                STDIN | "--stdin" => res.input.push(STDIN.to_string()),
+                "-f" | "--fmt" | "--format" => {
+                    outputs.push((args.next_arg()?, output.clone()))
+                }
+                "--text" => outputs.push((FmtType::Text, output.clone())),
+                "--tex-slides" | "--latex-slides" => {
+                    outputs.push((FmtType::LatexSlides, output.clone()))
+                }
+                "--tex-stage" | "--latex-stage" => {
+                    outputs.push((FmtType::LatexStage, output.clone()))
+                }
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => match conf.targets.iter().find(|(n, _)| n == v) {
+                    Some((_, t)) => targets.push(t.clone()),
+                    None => res.input.push(v.to_string()),
+                },
+            }
+        }
+
+        // Outputs and targets on the command line replace the configured
+        // outputs.
+        if outputs.is_empty() && targets.is_empty() {
+            outputs = conf.outputs.clone();
+            targets = conf.targets.iter().map(|(_, t)| t.clone()).collect();
+        }
+        res.outputs = outputs
+            .into_iter()
+            .map(|(fmt, path)| BuildTarget {
+                fmt,
+                path,
+                languages: vec![],
+                notes: conf.notes,
+                smart_quotes: conf.smart_quotes,
+            })
+            .chain(targets)
+            .map(|mut t| {
+                if !languages.is_empty() {
+                    t.languages = languages.clone();
+                } else if t.languages.is_empty() {
+                    t.languages = conf.language.iter().cloned().collect();
+                }
+                t.notes |= notes;
+                t.smart_quotes |= smart_quotes;
+                t
+            })
+            .collect();
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::Build(res))
+    }
+}
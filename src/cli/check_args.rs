@@ -0,0 +1,127 @@
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::{
+    encoding::InputEncoding,
+    lints::{LintConf, Severity},
+};
+
+use crate::cli::{
+    CmdSpec, Command, Config, MessageFormat, OptSpec, STDIN, ValueKind,
+};
+
+#[derive(Debug, Default)]
+pub struct CheckArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+    pub lints: LintConf,
+    pub message_format: MessageFormat,
+    /// Print the available lints instead of checking.
+    pub list_lints: bool,
+}
+
+impl CheckArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "check",
+        usage: "[options] [inputs]",
+        about: "Parse and validate songs without producing any output.",
+        description: "Parse and validate the input songs without producing \
+            any output. Besides errors that prevent building the songs, \
+            problems that are probably mistakes are reported as warnings. \
+            All inputs are checked even if some of them are invalid.",
+        options: &[
+            OptSpec::any(&["-A", "--allow"], "lint", "Don't report the lint."),
+            OptSpec::any(
+                &["-W", "--warn"],
+                "lint",
+                "Report the lint as warning.",
+            ),
+            OptSpec::any(
+                &["-D", "--deny"],
+                "lint",
+                "Report the lint as error.",
+            ),
+            OptSpec::choice(
+                &["--message-format"],
+                "format",
+                &MessageFormat::NAMES,
+                "Format of the reported problems.",
+            ),
+            OptSpec::flag(&["--list-lints"], "Print all the lints and exit."),
+            OptSpec::ENCODING,
+            OptSpec::STDIN,
+            OptSpec::INPUT,
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[
+            (
+                "Lints",
+                "Lints are selected by their code (e.g. `W001`) or name \
+                (e.g. `unused-verse`). `all` selects all lints and \
+                `warnings` selects all lints that are warnings by default. \
+                When multiple options select the same lint, the last one \
+                is used. The lint levels from `wohu.toml` are applied before \
+                the options. Severity of errors cannot be changed.",
+            ),
+            (
+                "Message format",
+                "With `--message-format json`, each problem is printed to \
+                stdout as JSON object on single line with the fields `file`, \
+                `severity`, `code`, `name`, `message`, `start`, `end` and \
+                `notes`. Positions have the fields `line` and `col` that \
+                start at 1.",
+            ),
+            (
+                "Exit codes",
+                "0  There are no errors.\n\
+                1  Some of the inputs have errors.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            lints: conf.lint_conf(),
+            ..Self::default()
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "-A" | "--allow" => {
+                    res.lints.set(args.next_arg()?, Severity::Allow)
+                }
+                "-W" | "--warn" => {
+                    res.lints.set(args.next_arg()?, Severity::Warning)
+                }
+                "-D" | "--deny" => {
+                    res.lints.set(args.next_arg()?, Severity::Error)
+                }
+                "--message-format" => res.message_format = args.next_arg()?,
+                "--list-lints" => res.list_lints = true,
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                STDIN | "--stdin" => res.input.push(STDIN.to_string()),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input.push(v.to_string()),
+            }
+        }
+
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::Check(res))
+    }
+}
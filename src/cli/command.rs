@@ -0,0 +1,22 @@
+use crate::cli::{
+    BuildArgs, CheckArgs, CompletionsArgs, ConfigArgs, DisplayArgs, FmtArgs,
+    ImportArgs, ListArgs, LspArgs, PresentArgs, ServeArgs,
+};
+
+#[derive(Debug)]
+pub enum Command {
+    /// Print help for the given command or the main help.
+    Help(Option<&'static str>),
+    Version,
+    Completions(CompletionsArgs),
+    Build(BuildArgs),
+    Check(CheckArgs),
+    Fmt(FmtArgs),
+    List(ListArgs),
+    Import(ImportArgs),
+    Lsp(LspArgs),
+    Present(PresentArgs),
+    Display(DisplayArgs),
+    Serve(ServeArgs),
+    Config(ConfigArgs),
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use pareg::Pareg;
+
+use crate::cli::{CmdSpec, Command, Shell, ValueKind};
+
+#[derive(Debug)]
+pub struct CompletionsArgs {
+    pub shell: Shell,
+}
+
+impl CompletionsArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "completions",
+        usage: "<shell>",
+        about: "Print shell completion script.",
+        description: "Print completion script for the given shell. Supported \
+            shells are `bash`, `zsh` and `fish`.",
+        options: &[],
+        positional: Some(ValueKind::Choice(&Shell::NAMES)),
+        sections: &[
+            (
+                "Installation",
+                "bash  wohu completions bash > \
+                ~/.local/share/bash-completion/completions/wohu\n\
+                zsh   wohu completions zsh > ~/.zfunc/_wohu\n\
+                fish  wohu completions fish > \
+                ~/.config/fish/completions/wohu.fish",
+            ),
+            (
+                "Exit codes",
+                "0  The script was printed.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg) -> Result<Command> {
+        let mut shell = None;
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                v if v.starts_with('-') => {
+                    return Err(args.err_unknown_argument().into());
+                }
+                _ => shell = Some(args.cur_arg()?),
+            }
+        }
+
+        let Some(shell) = shell else {
+            return Err(args
+                .err_no_more_arguments()
+                .main_msg("Missing the shell to generate completions for.")
+                .into());
+        };
+
+        Ok(Command::Completions(Self { shell }))
+    }
+}
@@ -0,0 +1,383 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use pareg::FromArg;
+use serde::{Deserialize, Serialize};
+use wohu::{
+    encoding::InputEncoding,
+    lints::{LintConf, LintSel, Severity},
+    out_fmt::{FmtType, OVERLAY_STYLE_NAMES},
+};
+
+use crate::cli::{BuildTarget, STDIN};
+
+/// Name of the project configuration file.
+pub const CONFIG_FILE: &str = "wohu.toml";
+
+/// Project configuration. It provides the defaults of the command line
+/// options, so options on the command line take precedence.
+#[derive(Debug, Default)]
+pub struct Config {
+    /// The loaded file. [`None`] if no file was found.
+    pub path: Option<PathBuf>,
+    /// Directory with the songs. Inputs that are not found are looked up in
+    /// it.
+    pub library: Option<PathBuf>,
+    pub encoding: InputEncoding,
+    /// Language of the `build` outputs that don't select languages and
+    /// the language selected in `present` and `serve`.
+    pub language: Option<String>,
+    pub smart_quotes: bool,
+    /// Outputs of `build` if there are no outputs on the command line.
+    pub outputs: Vec<(FmtType, Option<PathBuf>)>,
+    pub notes: bool,
+    pub keep_partial: bool,
+    /// Named targets of `build` in the order of their names.
+    pub targets: Vec<(String, BuildTarget)>,
+    pub max_lines: Option<usize>,
+    pub address: Option<String>,
+    /// Default style of the HTML overlays.
+    pub theme: Vec<(String, String)>,
+    /// Lint levels in the order in which they are applied.
+    pub lints: Vec<(String, LintSel, Severity)>,
+}
+
+/// Where the configuration is loaded from.
+#[derive(Debug, Clone, Default)]
+pub enum ConfigSource {
+    /// Find `wohu.toml` in the current or parent directory.
+    #[default]
+    Discover,
+    File(PathBuf),
+    /// Don't load any configuration (`--no-config`).
+    Disabled,
+}
+
+/// Failure to load the configuration. It is reported with different exit
+/// code than invalid arguments.
+#[derive(Debug)]
+pub struct ConfigError(anyhow::Error);
+
+/// Contents of the configuration file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    library: Option<PathBuf>,
+    encoding: Option<String>,
+    language: Option<String>,
+    smart_quotes: bool,
+    build: BuildSection,
+    present: PresentSection,
+    targets: BTreeMap<String, TargetEntry>,
+    theme: BTreeMap<String, toml::Value>,
+    lints: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct BuildSection {
+    outputs: Vec<OutputEntry>,
+    notes: bool,
+    keep_partial: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct OutputEntry {
+    format: String,
+    /// The output is written to stdout if there is no path.
+    path: Option<PathBuf>,
+}
+
+/// Named build target. Options that are not given are the same as for the
+/// outputs in `[build]`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct TargetEntry {
+    format: String,
+    path: Option<PathBuf>,
+    #[serde(default)]
+    languages: Vec<String>,
+    notes: Option<bool>,
+    smart_quotes: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct PresentSection {
+    max_lines: Option<usize>,
+    address: Option<String>,
+}
+
+impl ConfigSource {
+    pub fn load(&self) -> Result<Config> {
+        let res = match self {
+            Self::Discover => Config::discover(),
+            Self::File(p) => Config::load(p),
+            Self::Disabled => Ok(Config::default()),
+        };
+        res.map_err(|e| ConfigError(e).into())
+    }
+
+    /// Load the configuration, discovering it from the given directory
+    /// instead of the current directory.
+    pub fn load_in(&self, dir: &Path) -> Result<Config> {
+        match self {
+            Self::Discover => {
+                Config::discover_in(dir).map_err(|e| ConfigError(e).into())
+            }
+            s => s.load(),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Load `wohu.toml` from the current directory or the nearest parent
+    /// directory that has it. If there is no such file, the configuration is
+    /// empty.
+    pub fn discover() -> Result<Self> {
+        let cwd = env::current_dir()
+            .context("Failed to get the current directory.")?;
+        Self::discover_in(&cwd)
+    }
+
+    /// Load `wohu.toml` from the directory or its nearest parent that has
+    /// it.
+    pub fn discover_in(dir: &Path) -> Result<Self> {
+        for dir in dir.ancestors() {
+            let path = dir.join(CONFIG_FILE);
+            if path.is_file() {
+                return Self::load(&path);
+            }
+        }
+        Ok(Self::default())
+    }
+
+    /// Load the configuration file. Relative paths in the file are relative
+    /// to its directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let src = fs::read_to_string(path).map_err(|e| {
+            anyhow!("Failed to read `{}`: {e}", path.display())
+        })?;
+        let file: ConfigFile = toml::from_str(&src).map_err(|e| {
+            let msg = e.to_string();
+            anyhow!(
+                "Invalid configuration `{}`: {}",
+                path.display(),
+                msg.trim_end()
+            )
+        })?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut res = file.resolve(dir).map_err(|e| {
+            anyhow!("Invalid configuration `{}`: {e}", path.display())
+        })?;
+        res.path = Some(path.to_owned());
+        Ok(res)
+    }
+
+    /// Look up the input in the library if it is not found as it is.
+    pub fn resolve_input(&self, input: &mut String) {
+        let Some(lib) = &self.library else {
+            return;
+        };
+        let path = Path::new(input.as_str());
+        if input == STDIN || path.is_absolute() || path.exists() {
+            return;
+        }
+        let in_lib = lib.join(path);
+        if in_lib.exists() {
+            *input = in_lib.to_string_lossy().into_owned();
+        }
+    }
+
+    /// Lint levels from the configuration.
+    pub fn lint_conf(&self) -> LintConf {
+        let mut res = LintConf::default();
+        for (_, sel, severity) in &self.lints {
+            res.set(*sel, *severity);
+        }
+        res
+    }
+
+    /// Format the configuration as configuration file. All the values are
+    /// included, even the defaults.
+    pub fn to_toml(&self) -> Result<String> {
+        let file = ConfigFile {
+            library: self.library.clone(),
+            encoding: Some(self.encoding.name().to_string()),
+            language: self.language.clone(),
+            smart_quotes: self.smart_quotes,
+            build: BuildSection {
+                outputs: self
+                    .outputs
+                    .iter()
+                    .map(|(f, p)| OutputEntry {
+                        format: f.name().to_string(),
+                        path: p.clone(),
+                    })
+                    .collect(),
+                notes: self.notes,
+                keep_partial: self.keep_partial,
+            },
+            present: PresentSection {
+                max_lines: self.max_lines,
+                address: self.address.clone(),
+            },
+            targets: self
+                .targets
+                .iter()
+                .map(|(n, t)| {
+                    let t = TargetEntry {
+                        format: t.fmt.name().to_string(),
+                        path: t.path.clone(),
+                        languages: t.languages.clone(),
+                        notes: Some(t.notes),
+                        smart_quotes: Some(t.smart_quotes),
+                    };
+                    (n.clone(), t)
+                })
+                .collect(),
+            theme: self
+                .theme
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone().into()))
+                .collect(),
+            lints: self
+                .lints
+                .iter()
+                .map(|(l, _, s)| (l.clone(), severity_name(*s).to_string()))
+                .collect(),
+        };
+        Ok(toml::to_string_pretty(&file)?)
+    }
+}
+
+impl ConfigFile {
+    fn resolve(self, dir: &Path) -> Result<Config> {
+        let encoding = match &self.encoding {
+            Some(e) => InputEncoding::from_arg(e)
+                .map_err(|_| anyhow!("Unknown encoding `{e}`."))?,
+            None => InputEncoding::Auto,
+        };
+
+        let outputs = self
+            .build
+            .outputs
+            .into_iter()
+            .map(|o| Ok((parse_fmt(&o.format)?, o.path.map(|p| dir.join(p)))))
+            .collect::<Result<_>>()?;
+
+        let targets = self
+            .targets
+            .into_iter()
+            .map(|(n, t)| {
+                let target = BuildTarget {
+                    fmt: parse_fmt(&t.format)
+                        .map_err(|e| anyhow!("Target `{n}`: {e}"))?,
+                    path: t.path.map(|p| dir.join(p)),
+                    languages: t.languages,
+                    notes: t.notes.unwrap_or(self.build.notes),
+                    smart_quotes: t.smart_quotes.unwrap_or(self.smart_quotes),
+                };
+                Ok((n, target))
+            })
+            .collect::<Result<_>>()?;
+
+        let theme = self
+            .theme
+            .into_iter()
+            .map(|(k, v)| {
+                if !OVERLAY_STYLE_NAMES.contains(&k.as_str()) {
+                    bail!(
+                        "Unknown theme value `{k}`. Use one of: {}.",
+                        OVERLAY_STYLE_NAMES.join(", ")
+                    );
+                }
+                let v = match v {
+                    toml::Value::String(s) => s,
+                    toml::Value::Integer(i) => i.to_string(),
+                    toml::Value::Float(f) => f.to_string(),
+                    _ => bail!("Theme value `{k}` must be string or number."),
+                };
+                Ok((k, v))
+            })
+            .collect::<Result<_>>()?;
+
+        let mut lints = self
+            .lints
+            .into_iter()
+            .map(|(l, s)| {
+                let sel = LintSel::from_arg(&l).map_err(|_| {
+                    anyhow!(
+                        "Unknown lint `{l}`. Use `wohu check --list-lints` to \
+                        see all the lints."
+                    )
+                })?;
+                let severity = match s.as_str() {
+                    "allow" => Severity::Allow,
+                    "warn" => Severity::Warning,
+                    "deny" => Severity::Error,
+                    _ => bail!(
+                        "Invalid level `{s}` of lint `{l}`. Use `allow`, \
+                        `warn` or `deny`."
+                    ),
+                };
+                Ok((l, sel, severity))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Groups of lints are applied first, so that single lints override
+        // them.
+        lints.sort_by_key(|(_, sel, _)| match sel {
+            LintSel::All => 0,
+            LintSel::Warnings => 1,
+            LintSel::One(_) => 2,
+        });
+
+        Ok(Config {
+            path: None,
+            library: self.library.map(|l| dir.join(l)),
+            encoding,
+            language: self.language,
+            smart_quotes: self.smart_quotes,
+            outputs,
+            notes: self.build.notes,
+            keep_partial: self.build.keep_partial,
+            targets,
+            max_lines: self.present.max_lines,
+            address: self.present.address,
+            theme,
+            lints,
+        })
+    }
+}
+
+fn parse_fmt(name: &str) -> Result<FmtType> {
+    FmtType::from_arg(name).map_err(|_| {
+        anyhow!(
+            "Unknown output format `{name}`. Use one of: {}.",
+            FmtType::NAMES.join(", ")
+        )
+    })
+}
+
+fn severity_name(s: Severity) -> &'static str {
+    match s {
+        Severity::Allow => "allow",
+        Severity::Warning => "warn",
+        Severity::Error => "deny",
+    }
+}
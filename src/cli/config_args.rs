@@ -0,0 +1,109 @@
+use anyhow::Result;
+use pareg::{FromArg, Pareg};
+
+use crate::cli::{CmdSpec, Command, Config, ValueKind};
+
+#[derive(Debug, Clone, Copy, FromArg)]
+pub enum ConfigAction {
+    /// Print the effective configuration.
+    Show,
+}
+
+impl ConfigAction {
+    pub const NAMES: [&str; 1] = ["show"];
+}
+
+#[derive(Debug)]
+pub struct ConfigArgs {
+    pub action: ConfigAction,
+    pub config: Config,
+}
+
+impl ConfigArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "config",
+        usage: "<action>",
+        about: "Show the project configuration from `wohu.toml`.",
+        description: "Work with the project configuration. It is loaded from \
+            `wohu.toml` in the current directory or in the nearest parent \
+            directory that has it. The configuration provides defaults of \
+            the command line options, so options given on the command line \
+            take precedence. Use `wohu --config <path> <command>` to load \
+            other file or `wohu --no-config <command>` to ignore it. \
+            `wohu config show` prints the path of the file and the \
+            effective configuration with all the default values.",
+        options: &[],
+        positional: Some(ValueKind::Choice(&ConfigAction::NAMES)),
+        sections: &[
+            (
+                "Configuration file",
+                "library       Directory with the songs. Inputs that don't \
+                exist are looked up in it.\n\
+                encoding      Encoding of the inputs, same as `--encoding`.\n\
+                language      Language of `build` outputs without \
+                `languages` and the language selected in `present` and \
+                `serve`.\n\
+                smart-quotes  Same as `--smart-quotes`.\n\
+                [build]       `outputs` is an array of tables with `format` \
+                and optional `path` used when no output is given on the \
+                command line. Also `notes` and `keep-partial`.\n\
+                [targets.<name>]  Named target of `build` with `format`, \
+                optional `path`, `languages`, `notes` and `smart-quotes`, \
+                see `wohu help build`.\n\
+                [present]     `max-lines` and `address` of `wohu serve`.\n\
+                [theme]       Default style of the HTML overlays. The keys \
+                are the style parameters, see `wohu help build`.\n\
+                [lints]       Lint names or codes with the level `allow`, \
+                `warn` or `deny`. Groups (`all`, `warnings`) are applied \
+                before single lints.\n\
+                Relative paths are relative to the directory of the file.",
+            ),
+            (
+                "Example",
+                "library = \"songs\"\n\
+                [[build.outputs]]\n\
+                format = \"latex-slides\"\n\
+                path = \"out/slides.tex\"\n\
+                [targets.stage]\n\
+                format = \"latex-stage\"\n\
+                path = \"out/stage.tex\"\n\
+                languages = [\"cs\", \"en\"]\n\
+                [theme]\n\
+                size = 60\n\
+                [lints]\n\
+                unused-verse = \"deny\"",
+            ),
+            (
+                "Exit codes",
+                "0  The configuration was printed.\n\
+                1  The configuration file is invalid.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg, config: Config) -> Result<Command> {
+        let mut action = None;
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                v if v.starts_with('-') => {
+                    return Err(args.err_unknown_argument().into());
+                }
+                _ => action = Some(args.cur_arg()?),
+            }
+        }
+
+        let Some(action) = action else {
+            return Err(args
+                .err_no_more_arguments()
+                .main_msg("Missing the action, e.g. `show`.")
+                .into());
+        };
+
+        Ok(Command::Config(Self { action, config }))
+    }
+}
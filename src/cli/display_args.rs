@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use pareg::Pareg;
+
+use crate::cli::{CmdSpec, Command, ValueKind};
+
+#[derive(Debug)]
+pub struct DisplayArgs {
+    pub file: PathBuf,
+}
+
+impl DisplayArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "display",
+        usage: "<file>",
+        about: "Show slides written by `wohu present` fullscreen.",
+        description: "Show the contents of the file centered in the whole \
+            terminal and update it whenever the file changes. Use it in \
+            separate terminal window together with `wohu present -o \
+            <file>`. Press `q` to quit.",
+        options: &[],
+        positional: Some(ValueKind::Path),
+        sections: &[(
+            "Exit codes",
+            "0  The display was closed.\n\
+            2  Invalid command line arguments.",
+        )],
+    };
+
+    pub fn parse(args: &mut Pareg) -> Result<Command> {
+        let mut file = None;
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                v if v.starts_with('-') => {
+                    return Err(args.err_unknown_argument().into());
+                }
+                _ => file = Some(args.cur_arg()?),
+            }
+        }
+
+        let Some(file) = file else {
+            return Err(args
+                .err_no_more_arguments()
+                .main_msg("Missing the file to display.")
+                .into());
+        };
+
+        Ok(Command::Display(Self { file }))
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::encoding::InputEncoding;
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, STDIN, ValueKind};
+
+#[derive(Debug, Default)]
+pub struct FmtArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+    /// Print the formatted source instead of rewriting the files.
+    pub stdout: bool,
+    /// Only check whether the files are formatted.
+    pub check: bool,
+}
+
+impl FmtArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "fmt",
+        usage: "[options] [inputs]",
+        about: "Reformat song sources to the canonical form.",
+        description: "Rewrite the input files in the canonical wohu source \
+            format. Comments and empty lines that separate groups of items \
+            are preserved. Multiple empty lines are merged into one. Files \
+            are written back in their encoding, including the byte order \
+            mark. Output to stdout is always UTF-8.",
+        options: &[
+            OptSpec::flag(
+                &["--stdout"],
+                "Print the formatted sources instead of rewriting them.",
+            ),
+            OptSpec::flag(
+                &["--check"],
+                "Don't write anything, only list the files that are not \
+                formatted.",
+            ),
+            OptSpec::ENCODING,
+            OptSpec::STDIN,
+            OptSpec::INPUT,
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[(
+            "Exit codes",
+            "0  All inputs were formatted.\n\
+                1  Some of the inputs couldn't be formatted or with \
+                `--check`, some of\n   \
+                the inputs are not formatted.\n\
+                2  Invalid command line arguments.",
+        )],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            ..Self::default()
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "--stdout" => res.stdout = true,
+                "--check" => res.check = true,
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                STDIN | "--stdin" => res.input.push(STDIN.to_string()),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input.push(v.to_string()),
+            }
+        }
+
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::Fmt(res))
+    }
+}
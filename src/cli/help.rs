@@ -0,0 +1,203 @@
+use std::fmt::Write;
+
+use wohu::out_fmt::FmtType;
+
+use crate::cli::{
+    BuildArgs, CheckArgs, CmdSpec, CompletionsArgs, ConfigArgs, DisplayArgs,
+    FmtArgs, ImportArgs, ListArgs, LspArgs, OptSpec, PresentArgs, ServeArgs,
+};
+
+/// Maximum width of the help text.
+const WIDTH: usize = 79;
+
+/// Maximum width of the option column in the table of options.
+const OPT_WIDTH: usize = 26;
+
+pub const COMMANDS: [&CmdSpec; 11] = [
+    &BuildArgs::SPEC,
+    &CheckArgs::SPEC,
+    &FmtArgs::SPEC,
+    &ListArgs::SPEC,
+    &ImportArgs::SPEC,
+    &PresentArgs::SPEC,
+    &ServeArgs::SPEC,
+    &DisplayArgs::SPEC,
+    &LspArgs::SPEC,
+    &ConfigArgs::SPEC,
+    &CompletionsArgs::SPEC,
+];
+
+pub const GLOBAL_OPTIONS: [OptSpec; 4] = [
+    OptSpec::flag(&["-h", "--help"], "Show this help."),
+    OptSpec::flag(&["-V", "--version"], "Show the version of wohu."),
+    OptSpec::path(
+        &["--config"],
+        "path",
+        "Load the configuration from the file instead of `wohu.toml`. Must \
+        be before the command.",
+    ),
+    OptSpec::flag(
+        &["--no-config"],
+        "Don't load any configuration. Must be before the command.",
+    ),
+];
+
+const EXAMPLES: &str = "\
+wohu --text song.wohu
+  Print the song as plain text.
+wohu -o slides.tex --tex-slides *.wohu
+  Create LaTeX slides from all the songs in the current directory.
+wohu --text -o slides.tex --tex-slides a.wohu b.wohu
+  Print the songs as text and also create LaTeX slides from them.
+wohu check songs/*.wohu
+  Check that all the songs are valid.
+wohu check -D warnings -A unused-verse songs/*.wohu
+  Fail on all the warnings except unused verses.
+wohu fmt songs/*.wohu
+  Reformat all the songs.
+wohu fmt --check songs/*.wohu
+  List the songs that are not formatted.
+wohu import lyrics.txt -l cs -n \"Song name\" -o song.wohu
+  Convert plain text lyrics to a new song.";
+
+pub fn version() -> String {
+    format!("wohu {}", env!("CARGO_PKG_VERSION"))
+}
+
+pub fn find_command(name: &str) -> Option<&'static CmdSpec> {
+    COMMANDS.iter().find(|c| c.name == name).copied()
+}
+
+/// Help for the given command or the main help if the command is not known.
+pub fn help(cmd: Option<&str>) -> String {
+    match cmd.and_then(find_command) {
+        Some(c) => command_help(c),
+        None => main_help(),
+    }
+}
+
+fn main_help() -> String {
+    let mut res = format!(
+        "{}\nGenerate slides and documents from songs.\n\n\
+        Usage: wohu [command] [options] [inputs]\n",
+        version()
+    );
+
+    let commands = COMMANDS
+        .iter()
+        .map(|c| (c.name.to_string(), c.about))
+        .chain([("help".to_string(), "Show help for the given command.")]);
+    table(&mut res, "Commands", commands);
+
+    table(
+        &mut res,
+        "Options",
+        GLOBAL_OPTIONS.iter().map(|o| (o.signature(), o.help)),
+    );
+
+    table(
+        &mut res,
+        "Output formats",
+        FmtType::ALL
+            .iter()
+            .map(|f| (f.name().to_string(), f.description())),
+    );
+
+    section(
+        &mut res,
+        "Details",
+        "When the first argument is not a command, `build` is used. Use \
+        `wohu help <command>` or `wohu <command> --help` to show help for \
+        the given command. Defaults of the options are loaded from \
+        `wohu.toml` in the current or parent directory, see `wohu help \
+        config`.",
+    );
+    res += "\nExamples:\n";
+    for l in EXAMPLES.lines() {
+        _ = writeln!(res, "  {l}");
+    }
+
+    res
+}
+
+fn command_help(cmd: &CmdSpec) -> String {
+    let mut res = format!("Usage: wohu {} {}\n\n", cmd.name, cmd.usage);
+    wrap(&mut res, cmd.description, 0);
+
+    let opts = cmd.options.iter().chain([&OptSpec::HELP]);
+    table(&mut res, "Options", opts.map(|o| (o.signature(), o.help)));
+
+    for (title, text) in cmd.sections {
+        section(&mut res, title, text);
+    }
+
+    res
+}
+
+fn section(out: &mut String, title: &str, text: &str) {
+    _ = writeln!(out, "\n{title}:");
+    wrap(out, text, 2);
+}
+
+/// Write table with two columns. The second column is wrapped.
+fn table<S: AsRef<str>>(
+    out: &mut String,
+    title: &str,
+    rows: impl Iterator<Item = (S, &'static str)>,
+) {
+    let rows: Vec<_> = rows.collect();
+    let col = rows
+        .iter()
+        .map(|(s, _)| s.as_ref().len() + 4)
+        .filter(|w| *w <= OPT_WIDTH)
+        .max()
+        .unwrap_or(OPT_WIDTH);
+
+    _ = writeln!(out, "\n{title}:");
+    for (name, help) in rows {
+        let name = name.as_ref();
+        if name.len() + 4 > col {
+            _ = writeln!(out, "  {name}");
+            out.extend(std::iter::repeat_n(' ', col));
+        } else {
+            _ = write!(out, "  {name:<0$}", col - 2);
+        }
+        wrap_from(out, help, col, col);
+    }
+}
+
+/// Write the text indented by `indent` spaces and wrapped to `WIDTH`.
+/// Each line of the text is wrapped separately.
+fn wrap(out: &mut String, text: &str, indent: usize) {
+    out.extend(std::iter::repeat_n(' ', indent));
+    wrap_from(out, text, indent, indent);
+}
+
+/// Same as `wrap` but the first line starts at column `start` and the
+/// indentation is already written.
+fn wrap_from(out: &mut String, text: &str, mut start: usize, indent: usize) {
+    for (i, line) in text.lines().enumerate() {
+        if i != 0 {
+            out.extend(std::iter::repeat_n(' ', indent));
+            start = indent;
+        }
+
+        let mut col = start;
+        for (j, word) in line.split(' ').enumerate() {
+            let len = word.chars().count();
+            if j != 0 {
+                if col + 1 + len > WIDTH {
+                    out.push('\n');
+                    out.extend(std::iter::repeat_n(' ', indent));
+                    col = indent;
+                } else {
+                    out.push(' ');
+                    col += 1;
+                }
+            }
+            out.push_str(word);
+            col += len;
+        }
+        out.push('\n');
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::encoding::InputEncoding;
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, ValueKind};
+
+#[derive(Debug, Default)]
+pub struct ImportArgs {
+    pub input: Option<String>,
+    pub encoding: InputEncoding,
+    pub output: Option<PathBuf>,
+    pub language: Option<String>,
+    pub name: Option<String>,
+}
+
+impl ImportArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "import",
+        usage: "[options] <input>",
+        about: "Convert plain text lyrics to wohu source.",
+        description: "Convert plain text lyrics to wohu source. Verses in \
+            the input are separated by empty lines. Verses that repeat are \
+            imported only once.",
+        options: &[
+            OptSpec::path(
+                &["-o", "--output"],
+                "path",
+                "Write the result to the file instead of stdout.",
+            ),
+            OptSpec::flag(&["--stdout"], "Write the result to stdout."),
+            OptSpec::any(
+                &["-l", "--language"],
+                "lang",
+                "Put the song into the given language.",
+            ),
+            OptSpec::any(
+                &["-n", "--name"],
+                "name",
+                "Name of the song. Requires `--language`.",
+            ),
+            OptSpec::ENCODING,
+            OptSpec::path(&["-i", "--input"], "path", "Set the input file."),
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[(
+            "Exit codes",
+            "0  The input was imported.\n\
+                1  Failed to read the input or write the output.\n\
+                2  Invalid command line arguments.",
+        )],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            ..Self::default()
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "-o" | "--output" => res.output = Some(args.next_arg()?),
+                "--stdout" => res.output = None,
+                "-l" | "--language" => res.language = Some(args.next_arg()?),
+                "-n" | "--name" => res.name = Some(args.next_arg()?),
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input = Some(args.next_arg()?),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input = Some(v.to_string()),
+            }
+        }
+
+        if res.name.is_some() && res.language.is_none() {
+            return Err(args
+                .err_invalid()
+                .main_msg("`--name` requires `--language`.")
+                .hint("Song names are always bound to a language.")
+                .into());
+        }
+
+        Ok(Command::Import(res))
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::encoding::InputEncoding;
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, STDIN, ValueKind};
+
+#[derive(Debug, Default)]
+pub struct ListArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+}
+
+impl ListArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "list",
+        usage: "[options] [inputs]",
+        about: "Print songs, their languages, names and verse identifiers.",
+        description: "Print the languages of each input song together with \
+            its name and the identifiers of its verses in order.",
+        options: &[OptSpec::ENCODING, OptSpec::STDIN, OptSpec::INPUT],
+        positional: Some(ValueKind::Path),
+        sections: &[(
+            "Exit codes",
+            "0  All inputs were listed.\n\
+                1  Some of the inputs couldn't be parsed.\n\
+                2  Invalid command line arguments.",
+        )],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            ..Self::default()
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                STDIN | "--stdin" => res.input.push(STDIN.to_string()),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input.push(v.to_string()),
+            }
+        }
+
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::List(res))
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use pareg::Pareg;
+
+use crate::cli::{CmdSpec, Command, ConfigSource, OptSpec};
+
+#[derive(Debug, Default)]
+pub struct LspArgs {
+    /// Configuration with the lint levels. It is loaded when the client
+    /// tells the server the workspace root.
+    pub config: ConfigSource,
+}
+
+impl LspArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "lsp",
+        usage: "[options]",
+        about: "Run language server for editors.",
+        description: "Run Language Server Protocol server that communicates \
+            over stdin and stdout. It provides diagnostics from the parser \
+            and lints, go to definition, hover with the verse text, \
+            completion, rename of verses and formatting. Configure your \
+            editor to run `wohu lsp` for `.wohu` files. The lint levels are \
+            taken from `wohu.toml` found from the root of the workspace or \
+            from the current directory.",
+        options: &[OptSpec::flag(
+            &["--stdio"],
+            "Communicate over stdin and stdout. This is the default.",
+        )],
+        positional: None,
+        sections: &[(
+            "Exit codes",
+            "0  The server was shut down by the client.\n\
+            1  The server exited without shutdown or failed to communicate.\n\
+            2  Invalid command line arguments.",
+        )],
+    };
+
+    pub fn parse(args: &mut Pareg, config: ConfigSource) -> Result<Command> {
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "--stdio" => {}
+                _ => return Err(args.err_unknown_argument().into()),
+            }
+        }
+
+        Ok(Command::Lsp(Self { config }))
+    }
+}
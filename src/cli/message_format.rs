@@ -0,0 +1,14 @@
+use pareg::FromArg;
+
+#[derive(Debug, Clone, Copy, Default, FromArg)]
+pub enum MessageFormat {
+    /// Human readable messages.
+    #[default]
+    Human,
+    /// Each message is JSON object on single line.
+    Json,
+}
+
+impl MessageFormat {
+    pub const NAMES: [&str; 2] = ["human", "json"];
+}
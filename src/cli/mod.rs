@@ -1,3 +1,25 @@
 mod args;
+mod build_args;
+mod check_args;
+mod command;
+mod completions_args;
+mod config;
+mod config_args;
+mod display_args;
+mod fmt_args;
+pub mod help;
+mod import_args;
+mod list_args;
+mod lsp_args;
+mod message_format;
+mod present_args;
+mod serve_args;
+mod shell;
+mod spec;
 
-pub use self::args::*;
+pub use self::{
+    args::*, build_args::*, check_args::*, command::*, completions_args::*,
+    config::*, config_args::*, display_args::*, fmt_args::*, import_args::*,
+    list_args::*, lsp_args::*, message_format::*, present_args::*,
+    serve_args::*, shell::*, spec::*,
+};
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::encoding::InputEncoding;
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, ValueKind};
+
+#[derive(Debug, Default)]
+pub struct PresentArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+    pub setlist: Vec<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub language: Option<String>,
+    pub max_lines: Option<usize>,
+    pub smart_quotes: bool,
+    /// Default style of the live overlay.
+    pub theme: Vec<(String, String)>,
+    pub osc: Option<String>,
+    pub osc_feedback: Option<String>,
+    pub serve: Option<String>,
+}
+
+impl PresentArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "present",
+        usage: "[options] [inputs]",
+        about: "Present songs live from the terminal.",
+        description: "Present the input songs interactively. The terminal \
+            shows the current and the next slide and the operator moves \
+            through the songs with keys. The slide shown to the audience is \
+            written to the output file, which may be shown fullscreen in \
+            another terminal with `wohu display`, or to the web pages served \
+            with `--serve`. Changes done from the web control page are shown \
+            in the terminal too.",
+        options: &[
+            OptSpec::path(
+                &["-o", "--output"],
+                "path",
+                "Write the shown slide to the file.",
+            ),
+            OptSpec::any(
+                &["--serve"],
+                "addr",
+                "Also serve the web pages as `wohu serve` does.",
+            ),
+            OptSpec::path(
+                &["--setlist"],
+                "path",
+                "Add the songs listed in the file.",
+            ),
+            OptSpec::any(
+                &["-l", "--language"],
+                "lang",
+                "Start with the given language.",
+            ),
+            OptSpec::any(
+                &["--osc"],
+                "addr",
+                "Listen for OSC messages on the UDP address.",
+            ),
+            OptSpec::any(
+                &["--osc-feedback"],
+                "addr",
+                "Also send the OSC feedback to the address.",
+            ),
+            OptSpec::any(
+                &["--max-lines"],
+                "n",
+                "Split verses to slides with at most `n` lines.",
+            ),
+            OptSpec::flag(
+                &["--smart-quotes"],
+                "Use typographic quotes, dashes and ellipses of the song \
+                language.",
+            ),
+            OptSpec::ENCODING,
+            OptSpec::INPUT,
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[
+            (
+                "Keys",
+                "Right, Down, Space, j   Next slide.\n\
+                Left, Up, Backspace, k  Previous slide.\n\
+                PgDn, n                 Next song.\n\
+                PgUp, p                 Previous song.\n\
+                Home, g                 First slide of the song.\n\
+                <n> Enter               Slide number <n> of the song.\n\
+                <n> s                   Song number <n>.\n\
+                / <verse> Enter         Next slide with the verse.\n\
+                b, .                    Blank or unblank the display.\n\
+                l                       Switch to the next language.\n\
+                q, Esc, Ctrl+C          Quit.",
+            ),
+            (
+                "Setlist",
+                "Setlist is a file with one input file on each line. \
+                Relative paths are relative to the directory of the \
+                setlist. Empty lines and lines starting with `//` are \
+                ignored.",
+            ),
+            (
+                "OSC",
+                "With `--osc`, the presentation is also controlled by Open \
+                Sound Control messages. Songs and slides are numbered from \
+                1.\n\
+                /wohu/next, /wohu/prev        Next or previous slide.\n\
+                /wohu/song/next, /wohu/song/prev\n\
+                \x20                             Next or previous song.\n\
+                /wohu/song <n>                Song number `n`.\n\
+                /wohu/slide <n>               Slide number `n` of the song.\n\
+                /wohu/verse <id>              Next slide with the verse.\n\
+                /wohu/blank [<0|1>]           Toggle, unblank or blank.\n\
+                /wohu/language <lang>         Switch the language.\n\
+                /wohu/language/next           Switch to the next language.\n\
+                After each change, the messages `/wohu/song <n> <name>`, \
+                `/wohu/verse <id> <n>`, `/wohu/blank <0|1>` and \
+                `/wohu/language <lang>` are sent to everybody who sent any \
+                message and to the `--osc-feedback` address.",
+            ),
+            (
+                "Exit codes",
+                "0  The presentation ended.\n\
+                1  Failed to read or parse the inputs.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let mut res = Self {
+            encoding: conf.encoding,
+            language: conf.language.clone(),
+            max_lines: conf.max_lines,
+            smart_quotes: conf.smart_quotes,
+            theme: conf.theme.clone(),
+            ..Self::default()
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "-o" | "--output" => res.output = Some(args.next_arg()?),
+                "--serve" => res.serve = Some(args.next_arg()?),
+                "--setlist" => res.setlist.push(args.next_arg()?),
+                "-l" | "--language" => res.language = Some(args.next_arg()?),
+                "--osc" => res.osc = Some(args.next_arg()?),
+                "--osc-feedback" => res.osc_feedback = Some(args.next_arg()?),
+                "--max-lines" => res.max_lines = Some(args.next_arg()?),
+                "--smart-quotes" => res.smart_quotes = true,
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input.push(v.to_string()),
+            }
+        }
+
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::Present(res))
+    }
+}
@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use pareg::Pareg;
+use wohu::encoding::InputEncoding;
+
+use crate::cli::{CmdSpec, Command, Config, OptSpec, STDIN, ValueKind};
+
+#[derive(Debug)]
+pub struct ServeArgs {
+    pub input: Vec<String>,
+    pub encoding: InputEncoding,
+    pub setlist: Vec<PathBuf>,
+    pub language: Option<String>,
+    pub max_lines: Option<usize>,
+    pub smart_quotes: bool,
+    /// Default style of the live overlay.
+    pub theme: Vec<(String, String)>,
+    pub osc: Option<String>,
+    pub osc_feedback: Option<String>,
+    pub address: String,
+}
+
+impl Default for ServeArgs {
+    fn default() -> Self {
+        Self {
+            input: vec![],
+            encoding: InputEncoding::Auto,
+            setlist: vec![],
+            language: None,
+            max_lines: None,
+            smart_quotes: false,
+            theme: vec![],
+            osc: None,
+            osc_feedback: None,
+            address: "127.0.0.1:8080".into(),
+        }
+    }
+}
+
+impl ServeArgs {
+    pub const SPEC: CmdSpec = CmdSpec {
+        name: "serve",
+        usage: "[options] [inputs]",
+        about: "Present songs in web browser.",
+        description: "Start local web server with display page that shows \
+            the slides and control page that switches them. All the open \
+            pages are updated immediately when the presentation changes. \
+            The pages don't need internet connection.",
+        options: &[
+            OptSpec::any(
+                &["-a", "--address"],
+                "addr",
+                "Listen on the address. Default is `127.0.0.1:8080`.",
+            ),
+            OptSpec::path(
+                &["--setlist"],
+                "path",
+                "Add the songs listed in the file.",
+            ),
+            OptSpec::any(
+                &["-l", "--language"],
+                "lang",
+                "Start with the given language.",
+            ),
+            OptSpec::any(
+                &["--osc"],
+                "addr",
+                "Listen for OSC messages on the UDP address.",
+            ),
+            OptSpec::any(
+                &["--osc-feedback"],
+                "addr",
+                "Also send the OSC feedback to the address.",
+            ),
+            OptSpec::any(
+                &["--max-lines"],
+                "n",
+                "Split verses to slides with at most `n` lines.",
+            ),
+            OptSpec::flag(
+                &["--smart-quotes"],
+                "Use typographic quotes, dashes and ellipses of the song \
+                language.",
+            ),
+            OptSpec::ENCODING,
+            OptSpec::STDIN,
+            OptSpec::INPUT,
+        ],
+        positional: Some(ValueKind::Path),
+        sections: &[
+            (
+                "Pages",
+                "/         Display with the slide for the audience.\n\
+                /stage    Stage display for the singers with the current \
+                and the next verse and clock.\n\
+                /overlay  Transparent lyrics overlay for streaming. See \
+                `wohu help build` for its style.\n\
+                /control  Control page, also usable from phone.",
+            ),
+            (
+                "Remote control",
+                "By default, the pages are available only on this computer. \
+                Use `-a 0.0.0.0:8080` to control the presentation from other \
+                devices on the local network, e.g. from phone at \
+                `http://<ip of this computer>:8080/control`. Anybody on the \
+                network will be able to control the presentation.",
+            ),
+            (
+                "OSC",
+                "With `--osc`, the presentation is also controlled by Open \
+                Sound Control messages. Songs and slides are numbered from \
+                1.\n\
+                /wohu/next, /wohu/prev        Next or previous slide.\n\
+                /wohu/song/next, /wohu/song/prev\n\
+                \x20                             Next or previous song.\n\
+                /wohu/song <n>                Song number `n`.\n\
+                /wohu/slide <n>               Slide number `n` of the song.\n\
+                /wohu/verse <id>              Next slide with the verse.\n\
+                /wohu/blank [<0|1>]           Toggle, unblank or blank.\n\
+                /wohu/language <lang>         Switch the language.\n\
+                /wohu/language/next           Switch to the next language.\n\
+                After each change, the messages `/wohu/song <n> <name>`, \
+                `/wohu/verse <id> <n>`, `/wohu/blank <0|1>` and \
+                `/wohu/language <lang>` are sent to everybody who sent any \
+                message and to the `--osc-feedback` address.",
+            ),
+            (
+                "Exit codes",
+                "1  Failed to read or parse the inputs or to start the \
+                server.\n\
+                2  Invalid command line arguments.",
+            ),
+        ],
+    };
+
+    pub fn parse(args: &mut Pareg, conf: &Config) -> Result<Command> {
+        let default = Self::default();
+        let mut res = Self {
+            encoding: conf.encoding,
+            language: conf.language.clone(),
+            max_lines: conf.max_lines,
+            smart_quotes: conf.smart_quotes,
+            theme: conf.theme.clone(),
+            address: conf.address.clone().unwrap_or(default.address),
+            ..default
+        };
+
+        while let Some(a) = args.next() {
+            match a {
+                "-h" | "--help" => {
+                    return Ok(Command::Help(Some(Self::SPEC.name)));
+                }
+                "-a" | "--address" => res.address = args.next_arg()?,
+                "--setlist" => res.setlist.push(args.next_arg()?),
+                "-l" | "--language" => res.language = Some(args.next_arg()?),
+                "--osc" => res.osc = Some(args.next_arg()?),
+                "--osc-feedback" => res.osc_feedback = Some(args.next_arg()?),
+                "--max-lines" => res.max_lines = Some(args.next_arg()?),
+                "--smart-quotes" => res.smart_quotes = true,
+                "--encoding" => res.encoding = args.next_arg()?,
+                "-i" | "--input" => res.input.push(args.next_arg()?),
+                STDIN | "--stdin" => res.input.push(STDIN.to_string()),
+                v if v.starts_with('-') => {
+                    return Err(args
+                        .err_unknown_argument()
+                        .hint(
+                            "Use `-i` to specify input file that starts \
+                                with `-`.",
+                        )
+                        .into());
+                }
+                v => res.input.push(v.to_string()),
+            }
+        }
+
+        for i in &mut res.input {
+            conf.resolve_input(i);
+        }
+
+        Ok(Command::Serve(res))
+    }
+}
@@ -0,0 +1,12 @@
+use pareg::FromArg;
+
+#[derive(Debug, Clone, Copy, FromArg)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const NAMES: [&str; 3] = ["bash", "zsh", "fish"];
+}
@@ -0,0 +1,115 @@
+/// Input path that reads the songs from stdin.
+pub const STDIN: &str = "-";
+
+/// Description of a command. It is used to generate the help and the shell
+/// completion scripts.
+#[derive(Debug)]
+pub struct CmdSpec {
+    pub name: &'static str,
+    /// Arguments shown in the usage after the command name.
+    pub usage: &'static str,
+    /// One line summary of the command.
+    pub about: &'static str,
+    pub description: &'static str,
+    pub options: &'static [OptSpec],
+    /// Kind of the positional arguments.
+    pub positional: Option<ValueKind>,
+    /// Additional help sections as pairs of title and text.
+    pub sections: &'static [(&'static str, &'static str)],
+}
+
+/// Description of a single option of a command.
+#[derive(Debug)]
+pub struct OptSpec {
+    pub flags: &'static [&'static str],
+    pub value: Option<(&'static str, ValueKind)>,
+    pub help: &'static str,
+}
+
+/// Kind of value taken by an option.
+#[derive(Debug)]
+pub enum ValueKind {
+    Path,
+    Choice(&'static [&'static str]),
+    Any,
+}
+
+impl OptSpec {
+    pub const HELP: Self = Self::flag(&["-h", "--help"], "Show this help.");
+
+    pub const INPUT: Self = Self::path(
+        &["-i", "--input"],
+        "path",
+        "Add input file. Use this for inputs that start with `-`.",
+    );
+
+    pub const STDIN: Self = Self::flag(
+        &["--stdin"],
+        "Read songs from stdin. Same as the input `-`. Songs on stdin are \
+        separated by lines with `---`.",
+    );
+
+    pub const ENCODING: Self = Self::any(
+        &["--encoding"],
+        "encoding",
+        "Encoding of the input files, e.g. `windows-1250`. The default \
+        `auto` detects UTF-8, Windows-1250 and ISO-8859-2.",
+    );
+
+    pub const fn flag(
+        flags: &'static [&'static str],
+        help: &'static str,
+    ) -> Self {
+        Self {
+            flags,
+            value: None,
+            help,
+        }
+    }
+
+    pub const fn path(
+        flags: &'static [&'static str],
+        name: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            flags,
+            value: Some((name, ValueKind::Path)),
+            help,
+        }
+    }
+
+    pub const fn any(
+        flags: &'static [&'static str],
+        name: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            flags,
+            value: Some((name, ValueKind::Any)),
+            help,
+        }
+    }
+
+    pub const fn choice(
+        flags: &'static [&'static str],
+        name: &'static str,
+        choices: &'static [&'static str],
+        help: &'static str,
+    ) -> Self {
+        Self {
+            flags,
+            value: Some((name, ValueKind::Choice(choices))),
+            help,
+        }
+    }
+
+    /// Flags and value as shown in the help.
+    pub fn signature(&self) -> String {
+        let mut res = self.flags.join(", ");
+        if let Some((name, _)) = self.value {
+            res += &format!(" <{name}>");
+        }
+        res
+    }
+}
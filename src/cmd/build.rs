@@ -0,0 +1,170 @@
+use std::{
+    io::{BufWriter, Write, stdout},
+    mem,
+    path::PathBuf,
+    process::ExitCode,
+    ptr,
+};
+
+use anyhow::{Result, bail};
+use pareg::Pareg;
+use wohu::{
+    Batch, OutFmt, Song,
+    out_fmt::{self, FmtType},
+    render_parallel, typography,
+};
+
+use crate::{
+    cli::{Args, BuildArgs, Command, STDIN},
+    cmd::{inputs::parse_inputs, pending_file::PendingFile, watch::watch},
+};
+
+pub fn build(args: BuildArgs) -> Result<ExitCode> {
+    if args.watch {
+        if args.input.iter().any(|i| i == STDIN) {
+            bail!("Stdin cannot be watched for changes.");
+        }
+        let mut args = args;
+        let mut first = true;
+        watch(|files| {
+            // The configuration may change the inputs and where they are
+            // found, so the arguments are parsed again before each build.
+            // Invalid configuration fails the build and the previous files
+            // are watched until it is fixed.
+            if !mem::take(&mut first) {
+                args = reload()?;
+            }
+            *files = watched(&args);
+            build_once(&args)
+        });
+    }
+    build_once(&args)
+}
+
+/// Files that are watched in watch mode. The library is watched so that
+/// inputs that are added to it are found.
+fn watched(args: &BuildArgs) -> Vec<PathBuf> {
+    args.input
+        .iter()
+        .map(PathBuf::from)
+        .chain(args.config.clone())
+        .chain(args.library.clone())
+        .collect()
+}
+
+/// Parse the command line again with the current configuration.
+fn reload() -> Result<BuildArgs> {
+    match Args::parse(Pareg::args())?.command {
+        Command::Build(a) => Ok(a),
+        _ => unreachable!("the arguments were parsed as `build` before"),
+    }
+}
+
+fn build_once(args: &BuildArgs) -> Result<ExitCode> {
+    let mut files = vec![];
+    let mut outputs = vec![];
+    for (i, t) in args.outputs.iter().enumerate() {
+        let out: Box<dyn Write + Send> = if let Some(f) = &t.path {
+            let (pending, file) = PendingFile::create(f.clone(), i)?;
+            files.push(pending);
+            Box::new(BufWriter::new(file))
+        } else {
+            Box::new(stdout())
+        };
+
+        let of: Box<dyn OutFmt + Send> = match t.fmt {
+            FmtType::Text => {
+                Box::new(out_fmt::Text::new(out).with_notes(t.notes))
+            }
+            FmtType::LatexSlides => Box::new(out_fmt::Latex::new(out)),
+            FmtType::LatexStage => Box::new(out_fmt::LatexStage::new(out)),
+            FmtType::HtmlOverlay => Box::new(
+                out_fmt::HtmlOverlay::new(out).with_style(args.theme.clone()),
+            ),
+        };
+        outputs.push((t, of));
+    }
+
+    // The inputs are parsed once for all the targets. Songs with smart
+    // quotes are copied only if some targets don't use them.
+    let (mut songs, failures) = parse_inputs(&args.input, args.encoding)?;
+    let quote_all = args.outputs.iter().all(|t| t.smart_quotes);
+    let quoted: Vec<Song> =
+        if !quote_all && args.outputs.iter().any(|t| t.smart_quotes) {
+            songs.iter().map(|(_, s)| normalized(s.clone())).collect()
+        } else {
+            vec![]
+        };
+    if quote_all {
+        for (_, s) in &mut songs {
+            typography::normalize_song(s);
+        }
+    }
+    let songs: Vec<_> = songs.iter().map(|(_, s)| s).collect();
+    let quoted: Vec<_> = quoted.iter().collect();
+
+    // Outputs to stdout are rendered together so that they are written in
+    // the same order as if they were rendered serially. Each file is
+    // rendered in its own thread.
+    let mut groups: Vec<Vec<Batch<Box<dyn OutFmt + Send>>>> = vec![];
+    let mut stdout_group: Option<usize> = None;
+    for (t, of) in outputs {
+        let songs = if t.smart_quotes && !quote_all {
+            &quoted
+        } else {
+            &songs
+        };
+        let batch = Batch {
+            songs,
+            languages: &t.languages,
+            outputs: vec![of],
+        };
+        match (&t.path, stdout_group) {
+            (None, Some(g)) => {
+                let group = &mut groups[g];
+                let last = group.last_mut().unwrap();
+                if ptr::eq(last.songs, batch.songs)
+                    && last.languages == batch.languages
+                {
+                    last.outputs.extend(batch.outputs);
+                } else {
+                    group.push(batch);
+                }
+            }
+            (None, None) => {
+                stdout_group = Some(groups.len());
+                groups.push(vec![batch]);
+            }
+            (Some(_), _) => groups.push(vec![batch]),
+        }
+    }
+
+    let res = if failures.is_empty() || args.keep_partial {
+        render_parallel(&mut groups)
+    } else {
+        Ok(())
+    };
+    // Flush the outputs so that partial outputs are complete.
+    drop(groups);
+
+    // Files are replaced only if everything succeeded so that failure
+    // doesn't destroy the previous outputs.
+    if (res.is_ok() && failures.is_empty()) || args.keep_partial {
+        for f in files {
+            f.commit()?;
+        }
+    }
+    res?;
+
+    failures.print_summary();
+    if failures.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn normalized(mut song: Song) -> Song {
+    typography::normalize_song(&mut song);
+    song
+}
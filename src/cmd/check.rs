@@ -0,0 +1,155 @@
+use std::process::ExitCode;
+
+use anyhow::Result;
+use serde_json::json;
+use wohu::{
+    lints::{Diagnostic, Lint, Severity, lint_str},
+    syntax::{Pos, Span},
+};
+
+use crate::{
+    cli::{CheckArgs, MessageFormat},
+    cmd::inputs::{Source, count, invalid, sources},
+};
+
+pub fn check(args: CheckArgs) -> Result<ExitCode> {
+    if args.list_lints {
+        list_lints(&args);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut failed = 0;
+    let sources = sources(&args.input, args.encoding)?;
+    for input in &sources {
+        let diags = match input.read(args.encoding) {
+            Ok(src) => {
+                let diags = lint_str(&src, &args.lints);
+                for d in &diags {
+                    print_diagnostic(input, &src, d, args.message_format);
+                }
+                diags
+            }
+            Err(e) => {
+                let d = Diagnostic {
+                    lint: Lint::ParseError,
+                    severity: Severity::Error,
+                    span: Span::default(),
+                    message: e.to_string(),
+                    notes: vec![],
+                };
+                print_diagnostic(input, "", &d, args.message_format);
+                vec![d]
+            }
+        };
+
+        let errs = diags
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        errors += errs;
+        warnings += diags.len() - errs;
+        if errs != 0 {
+            failed += 1;
+        }
+    }
+
+    if matches!(args.message_format, MessageFormat::Human)
+        && (errors != 0 || warnings != 0)
+    {
+        eprintln!(
+            "Found {} and {}. {}",
+            count(errors, "error"),
+            count(warnings, "warning"),
+            invalid(failed, sources.len())
+        );
+    }
+
+    if errors == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn print_diagnostic(
+    input: &Source,
+    src: &str,
+    d: &Diagnostic,
+    format: MessageFormat,
+) {
+    let file = input.name();
+    let pos = |s: Span| {
+        (
+            input.pos(Pos::at(src, s.start)),
+            input.pos(Pos::at(src, s.end)),
+        )
+    };
+
+    match format {
+        MessageFormat::Human => {
+            let (start, _) = pos(d.span);
+            eprintln!(
+                "{file}:{start}: {}[{}]: {}",
+                d.severity.name(),
+                d.lint.code(),
+                d.message
+            );
+            for (span, msg) in &d.notes {
+                let (start, _) = pos(*span);
+                eprintln!("{file}:{start}: note: {msg}");
+            }
+        }
+        MessageFormat::Json => {
+            let pos_json = |p: Pos| json!({ "line": p.line, "col": p.col });
+            let (start, end) = pos(d.span);
+            let notes: Vec<_> = d
+                .notes
+                .iter()
+                .map(|(s, msg)| {
+                    let (start, end) = pos(*s);
+                    json!({
+                        "message": msg,
+                        "start": pos_json(start),
+                        "end": pos_json(end),
+                    })
+                })
+                .collect();
+            let msg = json!({
+                "file": file,
+                "severity": d.severity.name(),
+                "code": d.lint.code(),
+                "name": d.lint.name(),
+                "message": d.message,
+                "start": pos_json(start),
+                "end": pos_json(end),
+                "notes": notes,
+            });
+            println!("{msg}");
+        }
+    }
+}
+
+fn list_lints(args: &CheckArgs) {
+    for l in Lint::ALL {
+        let severity = args.lints.severity(l).name();
+        match args.message_format {
+            MessageFormat::Human => println!(
+                "{} {:<16} {severity:<8} {}",
+                l.code(),
+                l.name(),
+                l.description()
+            ),
+            MessageFormat::Json => println!(
+                "{}",
+                json!({
+                    "code": l.code(),
+                    "name": l.name(),
+                    "severity": severity,
+                    "description": l.description(),
+                })
+            ),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::{fmt::Write, process::ExitCode};
+
+use anyhow::Result;
+
+use crate::cli::{
+    BuildArgs, CmdSpec, CompletionsArgs, OptSpec, Shell, ValueKind,
+    help::{COMMANDS, GLOBAL_OPTIONS},
+};
+
+pub fn completions(args: CompletionsArgs) -> Result<ExitCode> {
+    let script = match args.shell {
+        Shell::Bash => bash()?,
+        Shell::Zsh => zsh()?,
+        Shell::Fish => fish()?,
+    };
+    print!("{script}");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn all_options(cmd: &CmdSpec) -> impl Iterator<Item = &OptSpec> {
+    cmd.options.iter().chain([&OptSpec::HELP])
+}
+
+fn command_names() -> String {
+    let names: Vec<_> = COMMANDS.iter().map(|c| c.name).collect();
+    names.join(" ") + " help"
+}
+
+/// First sentence of the help without characters that have special meaning
+/// in the completion scripts.
+fn short_help(help: &str) -> String {
+    let help = help.split(". ").next().unwrap_or_default();
+    help.trim_end_matches('.')
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '"' | '[' | ']' | ':' | '`'))
+        .collect()
+}
+
+fn bash() -> Result<String> {
+    let mut res = String::new();
+    writeln!(
+        res,
+        "_wohu() {{
+    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"
+    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"
+    local cmd={} opts files=1
+    case \"${{COMP_WORDS[1]}}\" in
+        {})
+            if [[ $COMP_CWORD -gt 1 ]]; then cmd=\"${{COMP_WORDS[1]}}\"; fi
+            ;;
+    esac
+
+    case \"$cmd\" in",
+        BuildArgs::SPEC.name,
+        COMMANDS.map(|c| c.name).join("|") + "|help"
+    )?;
+
+    for cmd in COMMANDS {
+        writeln!(res, "        {})", cmd.name)?;
+        writeln!(res, "            case \"$prev\" in")?;
+        for o in all_options(cmd) {
+            let Some((_, kind)) = &o.value else {
+                continue;
+            };
+            let compl = match kind {
+                ValueKind::Path => "compgen -f -- \"$cur\"".to_string(),
+                ValueKind::Choice(c) => {
+                    format!("compgen -W \"{}\" -- \"$cur\"", c.join(" "))
+                }
+                ValueKind::Any => "true".to_string(),
+            };
+            writeln!(
+                res,
+                "                {}) COMPREPLY=($({compl})); return;;",
+                o.flags.join("|")
+            )?;
+        }
+        writeln!(res, "            esac")?;
+
+        let flags: Vec<_> = all_options(cmd)
+            .flat_map(|o| o.flags.iter().copied())
+            .collect();
+        writeln!(res, "            opts=\"{}\"", flags.join(" "))?;
+        match &cmd.positional {
+            Some(ValueKind::Path) => {}
+            Some(ValueKind::Choice(c)) => {
+                writeln!(res, "            opts=\"$opts {}\"", c.join(" "))?;
+                writeln!(res, "            files=0")?;
+            }
+            _ => writeln!(res, "            files=0")?,
+        }
+        writeln!(res, "            ;;")?;
+    }
+
+    writeln!(
+        res,
+        "        help)
+            opts=\"{}\"
+            files=0
+            ;;",
+        COMMANDS.map(|c| c.name).join(" ")
+    )?;
+
+    let global: Vec<_> = GLOBAL_OPTIONS
+        .iter()
+        .flat_map(|o| o.flags.iter().copied())
+        .collect();
+    writeln!(
+        res,
+        "    esac
+
+    if [[ $COMP_CWORD -eq 1 ]]; then
+        opts=\"$opts {} {}\"
+    fi
+    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))
+    if [[ $files -eq 1 && \"$cur\" != -* ]]; then
+        COMPREPLY+=($(compgen -f -- \"$cur\"))
+    fi
+}}
+
+complete -o filenames -F _wohu wohu",
+        command_names(),
+        global.join(" ")
+    )?;
+
+    Ok(res)
+}
+
+fn zsh() -> Result<String> {
+    let mut res = String::new();
+    writeln!(
+        res,
+        "#compdef wohu
+
+_wohu() {{
+    local -a commands
+    commands=("
+    )?;
+    for cmd in COMMANDS {
+        writeln!(res, "        '{}:{}'", cmd.name, short_help(cmd.about))?;
+    }
+    writeln!(
+        res,
+        "        'help:Show help for the given command'
+    )
+
+    local cmd={}
+    if (( CURRENT > 2 )) && (( ${{commands[(I)${{words[2]}}:*]}} )); then
+        cmd=${{words[2]}}
+        shift words
+        (( CURRENT-- ))
+    elif (( CURRENT == 2 )); then
+        _describe -t commands 'wohu command' commands",
+        BuildArgs::SPEC.name
+    )?;
+    for o in &GLOBAL_OPTIONS {
+        for f in o.flags {
+            writeln!(res, "        compadd -J global -- {f}")?;
+        }
+    }
+    writeln!(
+        res,
+        "    fi
+
+    case $cmd in"
+    )?;
+
+    for cmd in COMMANDS {
+        writeln!(res, "        {})", cmd.name)?;
+        writeln!(res, "            _arguments -s \\")?;
+        for o in all_options(cmd) {
+            let value = match &o.value {
+                Some((name, ValueKind::Path)) => format!(":{name}:_files"),
+                Some((name, ValueKind::Choice(c))) => {
+                    format!(":{name}:({})", c.join(" "))
+                }
+                Some((name, ValueKind::Any)) => format!(":{name}: "),
+                None => String::new(),
+            };
+            let help = short_help(o.help);
+            for f in o.flags {
+                writeln!(res, "                '*{f}[{help}]{value}' \\")?;
+            }
+        }
+        match &cmd.positional {
+            Some(ValueKind::Path) => {
+                writeln!(res, "                '*:input:_files'")?
+            }
+            Some(ValueKind::Choice(c)) => {
+                writeln!(res, "                ':value:({})'", c.join(" "))?
+            }
+            _ => writeln!(res, "                '*: :'")?,
+        }
+        writeln!(res, "            ;;")?;
+    }
+
+    writeln!(
+        res,
+        "        help)
+            _describe -t commands 'wohu command' commands
+            ;;
+    esac
+}}
+
+_wohu \"$@\""
+    )?;
+
+    Ok(res)
+}
+
+fn fish() -> Result<String> {
+    let mut res = String::new();
+    let names = command_names();
+    writeln!(res, "complete -c wohu -f")?;
+
+    for cmd in COMMANDS {
+        writeln!(
+            res,
+            "complete -c wohu -n \"not __fish_seen_subcommand_from {names}\" \
+            -a {} -d '{}'",
+            cmd.name,
+            short_help(cmd.about)
+        )?;
+    }
+    writeln!(
+        res,
+        "complete -c wohu -n \"not __fish_seen_subcommand_from {names}\" \
+        -a help -d 'Show help for the given command'"
+    )?;
+    writeln!(
+        res,
+        "complete -c wohu -n \"__fish_seen_subcommand_from help\" -a '{}'",
+        COMMANDS.map(|c| c.name).join(" ")
+    )?;
+    for o in &GLOBAL_OPTIONS {
+        writeln!(
+            res,
+            "complete -c wohu -n \"__fish_is_first_arg\" {} -d '{}'",
+            fish_flags(o),
+            short_help(o.help)
+        )?;
+    }
+
+    for cmd in COMMANDS {
+        // Build is also used when there is no subcommand.
+        let cond = if cmd.name == BuildArgs::SPEC.name {
+            format!(
+                "__fish_seen_subcommand_from {0}; or not \
+                __fish_seen_subcommand_from {names}",
+                cmd.name
+            )
+        } else {
+            format!("__fish_seen_subcommand_from {}", cmd.name)
+        };
+
+        for o in all_options(cmd) {
+            let value = match &o.value {
+                Some((_, ValueKind::Path)) => " -r -F".to_string(),
+                Some((_, ValueKind::Choice(c))) => {
+                    format!(" -x -a '{}'", c.join(" "))
+                }
+                Some((_, ValueKind::Any)) => " -x".to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                res,
+                "complete -c wohu -n '{cond}' {}{value} -d '{}'",
+                fish_flags(o),
+                short_help(o.help)
+            )?;
+        }
+
+        match &cmd.positional {
+            Some(ValueKind::Path) => {
+                writeln!(res, "complete -c wohu -n '{cond}' -F")?
+            }
+            Some(ValueKind::Choice(c)) => writeln!(
+                res,
+                "complete -c wohu -n '{cond}' -a '{}'",
+                c.join(" ")
+            )?,
+            _ => {}
+        }
+    }
+
+    Ok(res)
+}
+
+fn fish_flags(o: &OptSpec) -> String {
+    let flags: Vec<_> = o
+        .flags
+        .iter()
+        .map(|f| {
+            if let Some(l) = f.strip_prefix("--") {
+                format!("-l {l}")
+            } else {
+                format!("-s {}", &f[1..])
+            }
+        })
+        .collect();
+    flags.join(" ")
+}
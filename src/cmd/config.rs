@@ -0,0 +1,21 @@
+use std::process::ExitCode;
+
+use anyhow::Result;
+
+use crate::cli::{Config, ConfigAction, ConfigArgs};
+
+pub fn config(args: ConfigArgs) -> Result<ExitCode> {
+    match args.action {
+        ConfigAction::Show => show(&args.config)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn show(config: &Config) -> Result<()> {
+    match &config.path {
+        Some(p) => println!("# {}", p.display()),
+        None => println!("# No `wohu.toml` found, the defaults are used."),
+    }
+    print!("{}", config.to_toml()?);
+    Ok(())
+}
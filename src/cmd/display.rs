@@ -0,0 +1,36 @@
+use std::{fs, process::ExitCode, time::Duration};
+
+use anyhow::Result;
+use termal::codes;
+
+use crate::{
+    cli::DisplayArgs,
+    present::{RawTerm, center, is_quit},
+};
+
+/// How often is the file checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn display(args: DisplayArgs) -> Result<ExitCode> {
+    let mut term = RawTerm::new()?;
+    let mut shown = None;
+    let mut size = (0, 0);
+
+    loop {
+        // Missing file is shown as blank screen.
+        let text = fs::read_to_string(&args.file).unwrap_or_default();
+        if shown.as_ref() != Some(&text) || size != term.size() {
+            size = term.size();
+            let lines: Vec<_> = text.lines().map(str::to_string).collect();
+            let lines = center(&lines, size.0, size.1)
+                .into_iter()
+                .map(|l| format!("{}{l}{}", codes::BOLD, codes::RESET));
+            term.draw_lines(lines)?;
+            shown = Some(text);
+        }
+
+        if term.read_key(POLL_INTERVAL)?.is_some_and(|k| is_quit(&k)) {
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fs, process::ExitCode};
+
+use anyhow::Result;
+
+use wohu::{
+    encoding::{self, InputEncoding, SourceEncoding},
+    source_fmt::format_str,
+};
+
+use crate::{
+    cli::FmtArgs,
+    cmd::inputs::{SONG_SEPARATOR, Source, sources},
+};
+
+pub fn fmt(args: FmtArgs) -> Result<ExitCode> {
+    let mut res = ExitCode::SUCCESS;
+    let mut stdin_songs = 0;
+    for src in sources(&args.input, args.encoding)? {
+        let name = src.name();
+        let (text, formatted, enc) = match fmt_source(&src, args.encoding) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("error: {name}: {e}");
+                res = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        if args.check {
+            if text != formatted {
+                println!("{name}");
+                res = ExitCode::FAILURE;
+            }
+        } else if let Some(path) = src.path()
+            && !args.stdout
+        {
+            // Files are written back in their encoding. Stdout is always
+            // UTF-8.
+            let write = || -> Result<()> {
+                Ok(fs::write(path, encoding::encode(&formatted, enc)?)?)
+            };
+            if text != formatted
+                && let Err(e) = write()
+            {
+                eprintln!("error: {name}: {e}");
+                res = ExitCode::FAILURE;
+            }
+        } else {
+            // Songs from stdin are written to stdout and they are separated
+            // in the same way as on the input.
+            if src.path().is_none() {
+                if stdin_songs != 0 {
+                    println!("{SONG_SEPARATOR}");
+                }
+                stdin_songs += 1;
+            }
+            print!("{formatted}");
+        }
+    }
+
+    Ok(res)
+}
+
+/// Returns the original and the formatted source and the encoding of the
+/// source.
+fn fmt_source(
+    src: &Source,
+    enc: InputEncoding,
+) -> Result<(String, String, SourceEncoding)> {
+    let (text, enc) = match src {
+        Source::File(path) => encoding::decode_source(&fs::read(path)?, enc)?,
+        Source::Stdin { src, .. } => (src.to_string(), SourceEncoding::UTF_8),
+    };
+    let formatted = format_str(&text).map_err(|e| src.relocate(e))?;
+    Ok((text, formatted, enc))
+}
@@ -0,0 +1,100 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{Write, stdout},
+    process::ExitCode,
+};
+
+use anyhow::{Result, bail};
+use wohu::{
+    encoding::read_file,
+    source_fmt::{escape_line, escape_string},
+};
+
+use crate::cli::ImportArgs;
+
+pub fn import(args: ImportArgs) -> Result<ExitCode> {
+    let Some(input) = &args.input else {
+        bail!("Missing input file to import.");
+    };
+
+    let res = import_str(
+        &read_file(input, args.encoding)?,
+        args.language.as_deref(),
+        args.name.as_deref(),
+    )?;
+
+    if let Some(o) = args.output {
+        File::create(o)?.write_all(res.as_bytes())?;
+    } else {
+        stdout().lock().write_all(res.as_bytes())?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Convert plain text lyrics to wohu source. Verses are separated by empty
+/// lines. Repeating verses are defined only once and named `r`, `r2`, ...
+/// while the other verses are named `v1`, `v2`, ...
+fn import_str(
+    src: &str,
+    language: Option<&str>,
+    name: Option<&str>,
+) -> Result<String> {
+    let mut verses: Vec<Vec<&str>> = vec![];
+    let mut order = vec![];
+    let mut cur = vec![];
+    for l in src.lines().map(str::trim).chain([""]) {
+        if !l.is_empty() {
+            cur.push(l);
+            continue;
+        }
+        if cur.is_empty() {
+            continue;
+        }
+        if let Some(i) = verses.iter().position(|v| *v == cur) {
+            order.push(i);
+            cur.clear();
+        } else {
+            order.push(verses.len());
+            verses.push(std::mem::take(&mut cur));
+        }
+    }
+
+    let (mut vi, mut ri) = (0, 0);
+    let ids: Vec<_> = (0..verses.len())
+        .map(|i| {
+            if order.iter().filter(|o| **o == i).count() > 1 {
+                ri += 1;
+                if ri == 1 {
+                    "r".to_string()
+                } else {
+                    format!("r{ri}")
+                }
+            } else {
+                vi += 1;
+                format!("v{vi}")
+            }
+        })
+        .collect();
+
+    let mut res = String::new();
+    if let Some(lang) = language {
+        writeln!(res, "#language {lang}")?;
+    }
+    if let Some(name) = name {
+        writeln!(res, "name = \"{}\"", escape_string(name))?;
+    }
+    let order: Vec<_> = order.iter().map(|i| ids[*i].as_str()).collect();
+    writeln!(res, "order = [{}]", order.join(", "))?;
+
+    for (id, v) in ids.iter().zip(verses) {
+        writeln!(res, "\n{id}: l{{")?;
+        for l in v {
+            writeln!(res, "    {}", escape_line(l))?;
+        }
+        writeln!(res, "}}")?;
+    }
+
+    Ok(res)
+}
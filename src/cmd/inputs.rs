@@ -0,0 +1,331 @@
+use std::{
+    borrow::Cow,
+    io::{Read, stdin},
+    panic,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use anyhow::{Context, Result, bail};
+use wohu::{
+    ParseError, ParseErrors, Song,
+    encoding::{InputEncoding, decode, read_file},
+    parse_str,
+    syntax::{Pos, SyntaxError},
+};
+
+use crate::cli::STDIN;
+
+/// Line that separates songs on stdin.
+pub const SONG_SEPARATOR: &str = "---";
+
+/// Source of single input song.
+pub enum Source<'a> {
+    /// Path to file that is read when needed.
+    File(&'a str),
+    /// Song read from stdin. `line` is the number of lines before the song
+    /// on stdin.
+    Stdin {
+        name: String,
+        src: String,
+        line: usize,
+    },
+}
+
+impl Source<'_> {
+    /// Name of the song in diagnostics.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::File(path) => path,
+            Self::Stdin { name, .. } => name,
+        }
+    }
+
+    /// Path of the file if the song is in file.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::File(path) => Some(path),
+            Self::Stdin { .. } => None,
+        }
+    }
+
+    /// Get the source of the song.
+    pub fn read(&self, encoding: InputEncoding) -> Result<Cow<'_, str>> {
+        match self {
+            Self::File(path) => read_file(path, encoding).map(Cow::Owned),
+            Self::Stdin { src, .. } => Ok(Cow::Borrowed(src)),
+        }
+    }
+
+    /// Convert position in the song to position in the whole input.
+    pub fn pos(&self, pos: Pos) -> Pos {
+        match self {
+            Self::File(_) => pos,
+            Self::Stdin { line, .. } => Pos {
+                line: pos.line + line,
+                ..pos
+            },
+        }
+    }
+
+    /// Convert positions of parse errors to positions in the whole input.
+    pub fn relocate(&self, mut e: anyhow::Error) -> anyhow::Error {
+        if let Some(ParseErrors(errs)) = e.downcast_mut() {
+            for e in errs {
+                e.pos = self.pos(e.pos);
+            }
+        } else if let Some(e) = e.downcast_mut::<ParseError>() {
+            e.pos = self.pos(e.pos);
+        } else if let Some(e) = e.downcast_mut::<SyntaxError>() {
+            e.pos = self.pos(e.pos);
+        }
+        e
+    }
+}
+
+/// Get the sources of the inputs. Input `-` reads all the songs from stdin.
+/// The songs on stdin are separated by lines with `---` and they are named
+/// `<stdin>`, or `<stdin>#1`, `<stdin>#2`, ... if there are more of them.
+/// Lines in the diagnostics of the songs from stdin are counted from the
+/// start of stdin.
+pub fn sources(
+    input: &[String],
+    encoding: InputEncoding,
+) -> Result<Vec<Source<'_>>> {
+    let mut res = vec![];
+    let mut stdin_read = false;
+    for ip in input {
+        if ip != STDIN {
+            res.push(Source::File(ip));
+            continue;
+        }
+        if stdin_read {
+            bail!("Stdin (`-`) may be given only once.");
+        }
+        stdin_read = true;
+
+        let mut bytes = vec![];
+        stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read stdin.")?;
+        let src = decode(&bytes, encoding).context("Failed to read stdin.")?;
+        let songs = split_songs(&src);
+        let cnt = songs.len();
+        res.extend(songs.into_iter().enumerate().map(|(i, (line, s))| {
+            let name = if cnt == 1 {
+                "<stdin>".to_string()
+            } else {
+                format!("<stdin>#{}", i + 1)
+            };
+            Source::Stdin {
+                name,
+                src: s.to_string(),
+                line,
+            }
+        }));
+    }
+    Ok(res)
+}
+
+/// Split the songs separated by lines with [`SONG_SEPARATOR`]. Empty songs
+/// are skipped unless the whole input is empty. The songs are returned with
+/// the number of lines before them.
+fn split_songs(src: &str) -> Vec<(usize, &str)> {
+    let mut res = vec![];
+    let mut start = (0, 0);
+    let mut pos = 0;
+    for (i, line) in src.split_inclusive('\n').enumerate() {
+        if line.trim_end() == SONG_SEPARATOR {
+            res.push((start.0, &src[start.1..pos]));
+            start = (i + 1, pos + line.len());
+        }
+        pos += line.len();
+    }
+    res.push((start.0, &src[start.1..]));
+
+    res.retain(|(_, s)| !s.trim().is_empty());
+    if res.is_empty() {
+        res.push((0, src));
+    }
+    res
+}
+
+/// Summary of errors in the parsed inputs.
+#[derive(Debug, Default)]
+pub struct Failures {
+    pub errors: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl Failures {
+    pub fn is_empty(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Print the summary to stderr if there are any errors.
+    pub fn print_summary(&self) {
+        if !self.is_empty() {
+            eprintln!(
+                "Found {}. {}",
+                count(self.errors, "error"),
+                invalid(self.failed, self.total)
+            );
+        }
+    }
+}
+
+/// Number with the noun in singular or plural, e.g. `1 error`, `2 errors`.
+pub fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{n} {noun}")
+    } else {
+        format!("{n} {noun}s")
+    }
+}
+
+/// Sentence with the number of invalid inputs.
+pub fn invalid(failed: usize, total: usize) -> String {
+    let verb = if failed == 1 { "is" } else { "are" };
+    format!("{failed} of {} {verb} invalid.", count(total, "input"))
+}
+
+/// Parse all the inputs in parallel. Errors in the inputs are printed to
+/// stderr in the order of the inputs and the inputs that failed are skipped.
+/// The songs are returned with their names, see [`sources`].
+pub fn parse_inputs(
+    input: &[String],
+    encoding: InputEncoding,
+) -> Result<(Vec<(String, Song)>, Failures)> {
+    let sources = sources(input, encoding)?;
+    let mut songs = vec![];
+    let mut failures = Failures {
+        total: sources.len(),
+        ..Default::default()
+    };
+
+    let results = parse_parallel(&sources, encoding);
+    for (src, res) in sources.iter().zip(results) {
+        let name = src.name();
+        let e = match res {
+            Ok(s) => {
+                songs.push((name.to_string(), s));
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        failures.failed += 1;
+        match e.downcast::<ParseErrors>() {
+            Ok(ParseErrors(errs)) => {
+                failures.errors += errs.len();
+                for e in errs {
+                    eprintln!("{name}:{}: error: {}", e.pos, e.msg);
+                }
+            }
+            Err(e) => {
+                failures.errors += 1;
+                eprintln!("{name}: error: {e}");
+            }
+        }
+    }
+
+    Ok((songs, failures))
+}
+
+/// Read and parse the sources in worker threads. Results are in the order
+/// of the sources.
+fn parse_parallel(
+    sources: &[Source],
+    encoding: InputEncoding,
+) -> Vec<Result<Song>> {
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(sources.len());
+    // Inputs differ in size, so the workers take them one by one instead of
+    // splitting them in advance.
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut res = vec![];
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(src) = sources.get(i) else {
+                return res;
+            };
+            let song = src.read(encoding).and_then(|s| parse_str(&s));
+            res.push((i, song.map_err(|e| src.relocate(e))));
+        }
+    };
+
+    let mut results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = (0..workers).map(|_| s.spawn(work)).collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Read the input files listed in the setlist. Relative paths are relative
+/// to the directory of the setlist. Empty lines and lines starting with `//`
+/// are ignored.
+pub fn read_setlist(
+    path: &Path,
+    encoding: InputEncoding,
+) -> Result<Vec<String>> {
+    let src = read_file(path, encoding).with_context(|| {
+        format!("Failed to read setlist `{}`.", path.display())
+    })?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    Ok(src
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"))
+        .map(|l| dir.join(l).to_string_lossy().into_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_songs() {
+        let src = "v1: l{ a }\n---\n\n---  \r\nv2: l{ b }\nv3: l{ c }\n---\n";
+        assert_eq!(
+            split_songs(src),
+            [(0, "v1: l{ a }\n"), (4, "v2: l{ b }\nv3: l{ c }\n")]
+        );
+        assert_eq!(split_songs(""), [(0, "")]);
+        assert_eq!(split_songs("---\n"), [(0, "---\n")]);
+        // Separator must be on its own line.
+        assert_eq!(split_songs("a --- b"), [(0, "a --- b")]);
+    }
+
+    #[test]
+    fn positions_on_stdin() {
+        let src = "v1: l{ a }\norder = [v1]\n---\nv1: l{ a }\norder = [v2]\n";
+        let sources: Vec<_> = split_songs(src)
+            .into_iter()
+            .map(|(line, s)| Source::Stdin {
+                name: String::new(),
+                src: s.to_string(),
+                line,
+            })
+            .collect();
+        assert!(
+            parse_str(&sources[0].read(InputEncoding::Auto).unwrap()).is_ok()
+        );
+
+        let song = sources[1].read(InputEncoding::Auto).unwrap();
+        let e = sources[1].relocate(parse_str(&song).unwrap_err());
+        let ParseErrors(errs) = e.downcast().unwrap();
+        assert_eq!(errs[0].pos, Pos { line: 5, col: 1 });
+
+        let pos = Pos { line: 2, col: 3 };
+        assert_eq!(Source::File("a.wohu").pos(pos), pos);
+    }
+}
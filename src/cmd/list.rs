@@ -0,0 +1,37 @@
+use std::process::ExitCode;
+
+use anyhow::Result;
+
+use crate::{cli::ListArgs, cmd::inputs::parse_inputs};
+
+pub fn list(args: ListArgs) -> Result<ExitCode> {
+    let (songs, failures) = parse_inputs(&args.input, args.encoding)?;
+    for (i, (ip, song)) in songs.iter().enumerate() {
+        if i != 0 {
+            println!();
+        }
+        println!("{ip}");
+        for conf in song.ordered_configs() {
+            let lang = song.idents.get_name(conf.language);
+            let default = if conf.language == song.default {
+                " (default)"
+            } else {
+                ""
+            };
+            println!("  {lang}{default}: {}", conf.name);
+            let order: Vec<_> = conf
+                .order
+                .iter()
+                .map(|v| song.idents.get_name(*v))
+                .collect();
+            println!("    {}", order.join(" "));
+        }
+    }
+
+    failures.print_summary();
+    if failures.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
@@ -0,0 +1,12 @@
+use std::{
+    io::{stdin, stdout},
+    process::ExitCode,
+};
+
+use anyhow::Result;
+
+use crate::{cli::LspArgs, lsp::Server};
+
+pub fn lsp(args: LspArgs) -> Result<ExitCode> {
+    Server::new(stdin().lock(), stdout().lock(), args.config).run()
+}
@@ -0,0 +1,19 @@
+mod build;
+mod check;
+mod completions;
+mod config;
+mod display;
+mod fmt;
+mod import;
+mod inputs;
+mod list;
+mod lsp;
+mod pending_file;
+mod present;
+mod serve;
+mod watch;
+
+pub use self::{
+    build::*, check::*, completions::*, config::*, display::*, fmt::*,
+    import::*, list::*, lsp::*, pending_file::*, present::*, serve::*,
+};
@@ -0,0 +1,116 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    process,
+};
+
+use anyhow::{Context, Result};
+
+/// Output file that is written to a temporary file next to it and moved to
+/// its place only when it is committed. If the pending file is dropped
+/// without commit, the temporary file is removed and the original file is
+/// left untouched.
+#[derive(Debug)]
+pub struct PendingFile {
+    tmp: PathBuf,
+    path: PathBuf,
+    done: bool,
+}
+
+impl PendingFile {
+    /// Create the temporary file for `path`. `idx` distinguishes multiple
+    /// pending files created by this process for the same path.
+    pub fn create(path: PathBuf, idx: usize) -> Result<(Self, File)> {
+        let name = path
+            .file_name()
+            .with_context(|| {
+                format!("Invalid output file `{}`.", path.display())
+            })?
+            .to_string_lossy();
+        let tmp = path
+            .with_file_name(format!(".{name}.{}.{idx}.tmp", process::id()));
+        let file = File::create(&tmp).with_context(|| {
+            format!("Failed to create output file `{}`.", path.display())
+        })?;
+        Ok((
+            Self {
+                tmp,
+                path,
+                done: false,
+            },
+            file,
+        ))
+    }
+
+    /// Move the temporary file to its place.
+    pub fn commit(mut self) -> Result<()> {
+        let res = fs::rename(&self.tmp, &self.path);
+        self.done = res.is_ok();
+        res.with_context(|| {
+            format!("Failed to write output file `{}`.", self.path.display())
+        })
+    }
+}
+
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        if !self.done {
+            _ = fs::remove_file(&self.tmp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("wohu-pending-{name}-{}", process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn commit_replaces_file() {
+        let dir = temp_dir("commit");
+        let path = dir.join("out.txt");
+        fs::write(&path, "old").unwrap();
+
+        let (pending, mut file) =
+            PendingFile::create(path.clone(), 0).unwrap();
+        file.write_all(b"new").unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        pending.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn drop_keeps_file() {
+        let dir = temp_dir("drop");
+        let path = dir.join("out.txt");
+        fs::write(&path, "old").unwrap();
+
+        let (pending, mut file) =
+            PendingFile::create(path.clone(), 0).unwrap();
+        file.write_all(b"new").unwrap();
+        drop(file);
+        drop(pending);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_path() {
+        assert!(PendingFile::create(PathBuf::from("/"), 0).is_err());
+    }
+}
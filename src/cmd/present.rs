@@ -0,0 +1,92 @@
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
+
+use anyhow::{Result, bail};
+use wohu::{encoding::InputEncoding, typography};
+
+use crate::{
+    cli::{PresentArgs, STDIN},
+    cmd::inputs::{parse_inputs, read_setlist},
+    present::{
+        Action, Deck, Display, FileDisplay, Live, OscServer, Presenter,
+        WebServer, run_tui,
+    },
+};
+
+pub fn present(args: PresentArgs) -> Result<ExitCode> {
+    if args.input.iter().any(|i| i == STDIN) {
+        bail!(
+            "The songs cannot be read from stdin, because the presentation \
+            is controlled from the terminal. Use `wohu serve` instead."
+        );
+    }
+    let Some(presenter) = prepare_presenter(
+        args.input,
+        &args.setlist,
+        args.language,
+        args.max_lines,
+        args.smart_quotes,
+        args.encoding,
+    )?
+    else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let live = Arc::new(Live::new(presenter));
+
+    if let Some(addr) = &args.osc {
+        OscServer::bind(addr, args.osc_feedback.as_deref(), live.clone())?
+            .spawn();
+    }
+
+    if let Some(addr) = &args.serve {
+        WebServer::bind(addr, live.clone())?
+            .with_overlay_style(&args.theme)
+            .spawn();
+    }
+
+    let mut displays: Vec<Box<dyn Display>> = vec![];
+    if let Some(o) = args.output {
+        displays.push(Box::new(FileDisplay::new(o)));
+    }
+
+    run_tui(&live, &mut displays)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Load the songs for presentation. Returns [`None`] if some of the inputs
+/// are invalid. The errors are already reported in that case.
+pub(super) fn prepare_presenter(
+    mut input: Vec<String>,
+    setlist: &[PathBuf],
+    language: Option<String>,
+    max_lines: Option<usize>,
+    smart_quotes: bool,
+    encoding: InputEncoding,
+) -> Result<Option<Presenter>> {
+    for s in setlist {
+        input.extend(read_setlist(s, encoding)?);
+    }
+
+    // Problems are reported before the presentation starts.
+    let (mut songs, failures) = parse_inputs(&input, encoding)?;
+    if !failures.is_empty() {
+        failures.print_summary();
+        return Ok(None);
+    }
+    if smart_quotes {
+        for (_, s) in &mut songs {
+            typography::normalize_song(s);
+        }
+    }
+
+    let mut deck = Deck::new(songs.iter().map(|(p, s)| (p.as_str(), s)));
+    if let Some(m) = max_lines {
+        deck.split_slides(m);
+    }
+    let mut presenter = Presenter::new(deck);
+    if let Some(l) = language
+        && !presenter.apply(Action::Language(l.clone()))
+    {
+        bail!("None of the songs has the language `{l}`.");
+    }
+    Ok(Some(presenter))
+}
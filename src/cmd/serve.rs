@@ -0,0 +1,37 @@
+use std::{process::ExitCode, sync::Arc};
+
+use anyhow::Result;
+
+use crate::{
+    cli::ServeArgs,
+    cmd::present::prepare_presenter,
+    present::{Live, OscServer, WebServer},
+};
+
+pub fn serve(args: ServeArgs) -> Result<ExitCode> {
+    let Some(presenter) = prepare_presenter(
+        args.input,
+        &args.setlist,
+        args.language,
+        args.max_lines,
+        args.smart_quotes,
+        args.encoding,
+    )?
+    else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let live = Arc::new(Live::new(presenter));
+
+    if let Some(addr) = &args.osc {
+        OscServer::bind(addr, args.osc_feedback.as_deref(), live.clone())?
+            .spawn();
+    }
+
+    let server =
+        WebServer::bind(&args.address, live)?.with_overlay_style(&args.theme);
+    let addr = server.local_addr()?;
+    println!("Display: http://{addr}/");
+    println!("Control: http://{addr}/control");
+    server.run()?;
+    Ok(ExitCode::SUCCESS)
+}
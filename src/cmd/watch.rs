@@ -0,0 +1,48 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::ExitCode,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+
+/// How often are the files checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Run `f` every time any of the watched files changes. `f` may change the
+/// watched files, so that they follow the inputs of the build. This never
+/// returns. Errors from `f` are printed and don't stop the watching.
+pub fn watch(mut f: impl FnMut(&mut Vec<PathBuf>) -> Result<ExitCode>) -> ! {
+    let mut files = vec![];
+    loop {
+        let cnt = files.len();
+        match f(&mut files) {
+            Ok(c) if c == ExitCode::SUCCESS => eprintln!("Build succeeded."),
+            Ok(_) => eprintln!("Build failed."),
+            Err(e) => eprintln!("error: {e}\nBuild failed."),
+        }
+        if files.len() != cnt {
+            eprintln!("Watching {} files for changes.", files.len());
+        }
+
+        let stamps = modified(&files);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if modified(&files) != stamps {
+                // Let the editor finish writing all the files.
+                thread::sleep(POLL_INTERVAL);
+                break;
+            }
+        }
+    }
+}
+
+/// Get the modification times of the files. Missing files have no time.
+fn modified(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|f| fs::metadata(f).and_then(|m| m.modified()).ok())
+        .collect()
+}
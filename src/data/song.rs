@@ -1,9 +1,46 @@
 use std::collections::HashMap;
 
-use crate::{data::SongConf, parse::IdentId};
+use crate::{
+    data::SongConf,
+    parse::{IdentId, IdentTable},
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Song {
     pub configs: HashMap<IdentId, SongConf>,
     pub default: IdentId,
+    pub idents: IdentTable,
+}
+
+impl Song {
+    pub fn default_config(&self) -> &SongConf {
+        &self.configs[&self.default]
+    }
+
+    /// Configurations ordered by the first appearance of their language
+    /// identifier.
+    pub fn ordered_configs(&self) -> Vec<&SongConf> {
+        let mut res: Vec<_> = self.configs.values().collect();
+        res.sort_by_key(|c| c.language);
+        res
+    }
+
+    /// Configurations of the languages in the given order. Language tags
+    /// are compared without case. If the song has none of the languages or
+    /// if no languages are given, the default configuration is used.
+    pub fn configs_for(&self, languages: &[String]) -> Vec<&SongConf> {
+        let res: Vec<_> = languages
+            .iter()
+            .filter_map(|l| {
+                self.configs.values().find(|c| {
+                    self.idents.get_name(c.language).eq_ignore_ascii_case(l)
+                })
+            })
+            .collect();
+        if res.is_empty() {
+            vec![self.default_config()]
+        } else {
+            res
+        }
+    }
 }
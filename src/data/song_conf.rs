@@ -1,8 +1,21 @@
+use std::sync::Arc;
+
 use crate::parse::IdentId;
 
-#[derive(Debug)]
+/// Single line of lyrics. Lines are shared, so the same verse in several
+/// places or languages is stored only once.
+pub type Line = Arc<str>;
+
+#[derive(Debug, Clone)]
 pub struct SongConf {
     pub language: IdentId,
-    pub _name: String,
-    pub verses: Vec<Vec<String>>,
+    pub name: String,
+    pub order: Vec<IdentId>,
+    pub verses: Vec<Vec<Line>>,
+    /// Notes for the operator about the whole song. They are never shown
+    /// to the audience.
+    pub notes: Vec<String>,
+    /// Notes for the operator for each verse in `verses`. Note of the verse
+    /// definition is followed by note of the entry in order.
+    pub verse_notes: Vec<Vec<String>>,
 }
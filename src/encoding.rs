@@ -0,0 +1,195 @@
+//! Decoding of input files that are not in UTF-8.
+
+use std::{fs, path::Path};
+
+use anyhow::{Result, bail};
+use encoding_rs::{
+    Encoding, ISO_8859_2, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1250,
+};
+use pareg::{ArgError, FromArg};
+
+/// Encoding of the input files.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum InputEncoding {
+    /// Inputs that are valid UTF-8 are UTF-8. Other inputs are Windows-1250
+    /// or ISO-8859-2, which are the common legacy encodings of Czech and
+    /// Slovak.
+    #[default]
+    Auto,
+    Fixed(&'static Encoding),
+}
+
+impl<'a> FromArg<'a> for InputEncoding {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        if arg.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        Encoding::for_label(arg.as_bytes())
+            .map(Self::Fixed)
+            .ok_or_else(|| {
+                ArgError::parse_msg("Unknown encoding.", arg.to_string()).hint(
+                    "Use `auto` or encoding label such as `utf-8`, \
+                    `windows-1250` or `iso-8859-2`.",
+                )
+            })
+    }
+}
+
+impl InputEncoding {
+    /// Name of the encoding as accepted by `--encoding`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Fixed(e) => e.name(),
+        }
+    }
+}
+
+/// Encoding of decoded source, so that it can be written back in the same
+/// form.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceEncoding {
+    pub encoding: &'static Encoding,
+    /// Whether the source starts with byte order mark.
+    pub bom: bool,
+}
+
+impl SourceEncoding {
+    pub const UTF_8: Self = Self {
+        encoding: UTF_8,
+        bom: false,
+    };
+}
+
+/// Decode the input. Byte order mark is removed and it takes precedence
+/// over the given encoding.
+pub fn decode(bytes: &[u8], enc: InputEncoding) -> Result<String> {
+    decode_source(bytes, enc).map(|(s, _)| s)
+}
+
+/// Same as [`decode`], but also returns the used encoding.
+pub fn decode_source(
+    bytes: &[u8],
+    enc: InputEncoding,
+) -> Result<(String, SourceEncoding)> {
+    let (encoding, bytes, bom) = match (Encoding::for_bom(bytes), enc) {
+        (Some((e, len)), _) => (e, &bytes[len..], true),
+        (None, InputEncoding::Fixed(e)) => (e, bytes, false),
+        (None, InputEncoding::Auto) => (detect(bytes), bytes, false),
+    };
+    match encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+        Some(s) => Ok((s.into_owned(), SourceEncoding { encoding, bom })),
+        None => bail!(
+            "The input is not valid {}. Use `--encoding` to select the \
+            encoding.",
+            encoding.name()
+        ),
+    }
+}
+
+/// Encode the text in the encoding of the source, including its byte order
+/// mark.
+pub fn encode(text: &str, enc: SourceEncoding) -> Result<Vec<u8>> {
+    // Encoders of encoding_rs write UTF-16 as UTF-8, so it is encoded here.
+    if enc.encoding == UTF_16LE || enc.encoding == UTF_16BE {
+        let be = enc.encoding == UTF_16BE;
+        let bom = enc.bom.then_some(0xfeff);
+        return Ok(bom
+            .into_iter()
+            .chain(text.encode_utf16())
+            .flat_map(|c| if be { c.to_be_bytes() } else { c.to_le_bytes() })
+            .collect());
+    }
+
+    let mut res = vec![];
+    if enc.bom {
+        res.extend_from_slice("\u{feff}".as_bytes());
+    }
+    let (bytes, _, unmappable) = enc.encoding.encode(text);
+    if unmappable {
+        bail!("The text cannot be encoded in {}.", enc.encoding.name());
+    }
+    res.extend_from_slice(&bytes);
+    Ok(res)
+}
+
+/// Read and decode file.
+pub fn read_file(
+    path: impl AsRef<Path>,
+    enc: InputEncoding,
+) -> Result<String> {
+    decode(&fs::read(path)?, enc)
+}
+
+fn detect(bytes: &[u8]) -> &'static Encoding {
+    if str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    // Windows-1250 has letters such as `š` and `ž` and the quotes `„“`
+    // at 0x80-0x9f, where ISO-8859-2 has only control characters. ISO-8859-2
+    // has the letters with caron at places of symbols in Windows-1250.
+    let control = bytes.iter().any(|b| (0x80..0xa0).contains(b));
+    let caron = bytes
+        .iter()
+        .any(|b| matches!(b, 0xa9 | 0xab | 0xae | 0xb9 | 0xbb | 0xbe));
+    if !control && caron {
+        ISO_8859_2
+    } else {
+        WINDOWS_1250
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Příliš žluťoučký kůň úpěl „ďábelské“ ódy.";
+
+    #[test]
+    fn detects_encoding() {
+        let cases = [
+            (TEXT.as_bytes().to_vec(), UTF_8),
+            (WINDOWS_1250.encode(TEXT).0.into_owned(), WINDOWS_1250),
+            (ISO_8859_2.encode("šťastná žába").0.into_owned(), ISO_8859_2),
+        ];
+        for (bytes, encoding) in cases {
+            let (text, enc) =
+                decode_source(&bytes, InputEncoding::Auto).unwrap();
+            assert_eq!(enc.encoding, encoding);
+            assert!(!enc.bom);
+            assert_eq!(encode(&text, enc).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn bom_round_trip() {
+        for encoding in [UTF_8, UTF_16LE, UTF_16BE] {
+            let bytes = encode(
+                TEXT,
+                SourceEncoding {
+                    encoding,
+                    bom: true,
+                },
+            )
+            .unwrap();
+            // Byte order mark takes precedence over the given encoding.
+            let fixed = InputEncoding::Fixed(WINDOWS_1250);
+            let (text, enc) = decode_source(&bytes, fixed).unwrap();
+            assert_eq!(text, TEXT);
+            assert_eq!(enc.encoding, encoding);
+            assert!(enc.bom);
+            assert_eq!(encode(&text, enc).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        let fixed = InputEncoding::Fixed(UTF_8);
+        assert!(decode(b"a\xffb", fixed).is_err());
+        let latin2 = SourceEncoding {
+            encoding: ISO_8859_2,
+            bom: false,
+        };
+        assert!(encode("„a“", latin2).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Parser and renderers for songs written in the wohu language.
+//!
+//! Songs are parsed with [`parse_str`], [`parse_read`] or [`parse_file`] into
+//! [`Song`]. Songs may be rendered with [`render`] to any [`OutFmt`], either
+//! one from [`out_fmt`] or custom one.
+//!
+//! Tools that work with the sources themselves may use [`lints`] to find
+//! problems, [`source_fmt`] to format them and [`syntax`] for the lossless
+//! syntax tree.
+
+mod data;
+pub mod encoding;
+pub mod lints;
+pub mod out_fmt;
+mod parse;
+mod render;
+pub mod source_fmt;
+pub mod syntax;
+pub mod typography;
+
+pub use self::{
+    data::*,
+    out_fmt::OutFmt,
+    parse::{
+        Ident, IdentId, IdentTable, ParseError, ParseErrors, parse_file,
+        parse_read, parse_str,
+    },
+    render::*,
+};
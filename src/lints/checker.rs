@@ -0,0 +1,639 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    mem,
+};
+
+use crate::{
+    lints::{Diagnostic, Lint, LintConf, Severity},
+    parse::{Token, normalize_lang_tag},
+    syntax::{
+        Item, NameEntry, OrderEntry, Separated, Span, SyntaxToken, SyntaxTree,
+        decode_line_string,
+    },
+    typography::{LangRules, rules_for},
+};
+
+/// Index of scope and index of verse within the scope.
+type VerseId = (usize, usize);
+
+/// Definitions in single scope. The first scope is the top level and each
+/// `#language` block has its own scope.
+#[derive(Default)]
+struct Scope<'t> {
+    lang: Option<&'t SyntaxToken>,
+    verses: Vec<(&'t SyntaxToken, &'t [Separated])>,
+    /// Keyword and entries of each order.
+    orders: Vec<(&'t SyntaxToken, &'t [OrderEntry])>,
+    names: Vec<&'t SyntaxToken>,
+    name_entries: Vec<&'t NameEntry>,
+}
+
+/// Finds problems in the song that are not syntax errors.
+pub struct Checker<'a> {
+    tree: &'a SyntaxTree,
+    conf: &'a LintConf,
+    scopes: Vec<Scope<'a>>,
+    /// Used verses with the configurations that use them.
+    used: HashMap<VerseId, Vec<usize>>,
+    /// Later definitions of verses that are already defined in the same
+    /// scope. They are ignored, the first definition is used.
+    redefined: HashSet<VerseId>,
+    res: Vec<Diagnostic>,
+    reported: HashSet<(Lint, Span)>,
+}
+
+impl<'a> Checker<'a> {
+    pub fn new(tree: &'a SyntaxTree, conf: &'a LintConf) -> Self {
+        Self {
+            tree,
+            conf,
+            scopes: vec![],
+            used: HashMap::new(),
+            redefined: HashSet::new(),
+            res: vec![],
+            reported: HashSet::new(),
+        }
+    }
+
+    pub fn check(mut self) -> Vec<Diagnostic> {
+        self.collect_scopes();
+        self.redefinitions();
+        self.shadowed_verses();
+
+        for c in self.configs() {
+            self.check_config(c);
+        }
+
+        self.unused_verses();
+        self.order_lengths();
+        self.unused_names();
+        self.empty_verses();
+        self.typography();
+
+        self.res.sort_by_key(|d| d.span.start);
+        self.res
+    }
+
+    fn collect_scopes(&mut self) {
+        self.scopes.push(Scope::default());
+        for item in &self.tree.items {
+            let top = self.scopes.len() == 1;
+            match item {
+                Item::Language { lang, .. } => {
+                    self.check_lang_tag(lang);
+                    self.scopes.push(Scope {
+                        lang: Some(lang),
+                        ..Default::default()
+                    })
+                }
+                Item::Verse { ident, terms, .. } => {
+                    self.scope().verses.push((ident, terms))
+                }
+                Item::Order { kw, entries, .. } => {
+                    self.scope().orders.push((kw, entries))
+                }
+                Item::Names { kw, .. } if !top => self.report(
+                    Lint::ParseError,
+                    kw.span,
+                    "Name in `#language` block must be single string.".into(),
+                ),
+                Item::Name { kw, .. } if top => self.report(
+                    Lint::ParseError,
+                    kw.span,
+                    "Name outside of `#language` block must be map from \
+                    language to name."
+                        .into(),
+                ),
+                Item::Names { kw, entries, .. } => {
+                    for e in entries {
+                        self.check_lang_tag(&e.lang);
+                    }
+                    let scope = self.scope();
+                    scope.names.push(kw);
+                    scope.name_entries.extend(entries);
+                }
+                Item::Name { kw, .. } => self.scope().names.push(kw),
+                Item::Note { .. } => {}
+            }
+        }
+    }
+
+    fn redefinitions(&mut self) {
+        let scopes = mem::take(&mut self.scopes);
+        let mut langs: HashMap<String, &SyntaxToken> = HashMap::new();
+        for (s, scope) in scopes.iter().enumerate() {
+            if let Some(lang) = scope.lang {
+                let name = self.lang_name(lang);
+                if let Some(prev) = langs.insert(name.clone(), lang) {
+                    self.redefinition(
+                        lang,
+                        prev,
+                        format!("Language `{name}` is already defined."),
+                    );
+                }
+            }
+
+            let mut verses: HashMap<&str, usize> = HashMap::new();
+            for (i, (ident, _)) in scope.verses.iter().enumerate() {
+                let name = self.tree.text(ident);
+                let prev = match verses.entry(name) {
+                    Entry::Occupied(prev) => *prev.get(),
+                    Entry::Vacant(e) => {
+                        e.insert(i);
+                        continue;
+                    }
+                };
+                self.redefined.insert((s, i));
+                self.redefinition(
+                    ident,
+                    scope.verses[prev].0,
+                    format!(
+                        "Verse `{name}` is already defined in this scope."
+                    ),
+                );
+            }
+
+            if let [first, rest @ ..] = scope.orders.as_slice() {
+                for (kw, _) in rest {
+                    self.redefinition(
+                        kw,
+                        first.0,
+                        "`order` is already defined in this scope.".into(),
+                    );
+                }
+            }
+
+            // Multiple name maps on the top level are merged.
+            if let (Some(_), [first, rest @ ..]) =
+                (scope.lang, scope.names.as_slice())
+            {
+                for kw in rest {
+                    self.redefinition(
+                        kw,
+                        first,
+                        "`name` is already defined in this scope.".into(),
+                    );
+                }
+            }
+
+            let mut names: HashMap<String, &SyntaxToken> = HashMap::new();
+            for e in &scope.name_entries {
+                let lang = self.lang_name(&e.lang);
+                if let Some(prev) = names.insert(lang.clone(), &e.lang) {
+                    self.redefinition(
+                        &e.lang,
+                        prev,
+                        format!(
+                            "Name for language `{lang}` is already given."
+                        ),
+                    );
+                }
+            }
+        }
+        self.scopes = scopes;
+    }
+
+    fn shadowed_verses(&mut self) {
+        let scopes = mem::take(&mut self.scopes);
+        if let [top, langs @ ..] = scopes.as_slice() {
+            for (ident, _) in langs.iter().flat_map(|s| &s.verses) {
+                let name = self.tree.text(ident);
+                let Some((prev, _)) =
+                    top.verses.iter().find(|(i, _)| self.tree.text(i) == name)
+                else {
+                    continue;
+                };
+                self.report_with(
+                    Lint::ShadowedVerse,
+                    ident.span,
+                    format!(
+                        "Verse `{name}` shadows verse from the top level."
+                    ),
+                    vec![(prev.span, "Shadowed definition is here.".into())],
+                );
+            }
+        }
+        self.scopes = scopes;
+    }
+
+    /// Check that all the verses in the configuration for the language in
+    /// the given scope can be evaluated.
+    fn check_config(&mut self, c: usize) {
+        let Some((_, order)) = self.order(c) else {
+            return;
+        };
+
+        let mut done = HashSet::new();
+        for e in order {
+            match self.resolve(c, &e.tok) {
+                Some(v) => self.walk(c, v, &mut vec![], &mut done),
+                None => self.undefined(c, &e.tok),
+            }
+        }
+    }
+
+    fn walk(
+        &mut self,
+        c: usize,
+        v: VerseId,
+        stack: &mut Vec<VerseId>,
+        done: &mut HashSet<VerseId>,
+    ) {
+        let users = self.used.entry(v).or_default();
+        if !users.contains(&c) {
+            users.push(c);
+        }
+        stack.push(v);
+        let terms = self.scopes[v.0].verses[v.1].1;
+        for t in terms.iter().filter(|t| t.tok.kind == Token::Ident) {
+            let Some(d) = self.resolve(c, &t.tok) else {
+                self.undefined(c, &t.tok);
+                continue;
+            };
+            if stack.contains(&d) {
+                let name = self.tree.text(&t.tok);
+                self.report(
+                    Lint::CyclicVerse,
+                    t.tok.span,
+                    format!("Cycle detected: `{name}` depends on itself."),
+                );
+            } else if !done.contains(&d) {
+                self.walk(c, d, stack, done);
+            }
+        }
+        stack.pop();
+        done.insert(v);
+    }
+
+    fn unused_verses(&mut self) {
+        let scopes = mem::take(&mut self.scopes);
+        for (s, scope) in scopes.iter().enumerate() {
+            for (i, (ident, _)) in scope.verses.iter().enumerate() {
+                let v = (s, i);
+                if self.used.contains_key(&v) || self.redefined.contains(&v) {
+                    continue;
+                }
+                let name = self.tree.text(ident);
+                self.report(
+                    Lint::UnusedVerse,
+                    ident.span,
+                    format!("Verse `{name}` is never used."),
+                );
+            }
+        }
+        self.scopes = scopes;
+    }
+
+    fn order_lengths(&mut self) {
+        let configs = self.configs();
+        if configs.len() < 2 {
+            return;
+        }
+
+        let lens: Vec<_> = configs
+            .into_iter()
+            .map(|c| {
+                let lang = self.scopes[c].lang.unwrap();
+                match self.order(c) {
+                    Some((kw, o)) if !self.scopes[c].orders.is_empty() => {
+                        (lang, kw, o.len())
+                    }
+                    o => (lang, lang, o.map_or(0, |(_, o)| o.len())),
+                }
+            })
+            .collect();
+
+        let (first_lang, first_span, first_len) = lens[0];
+        let first_name = self.tree.text(first_lang);
+        for (lang, span, len) in &lens[1..] {
+            if *len == first_len {
+                continue;
+            }
+            let name = self.tree.text(lang);
+            let msg = format!(
+                "Order for language `{name}` has {len} verses but order for \
+                language `{first_name}` has {first_len}."
+            );
+            self.report_with(
+                Lint::OrderLength,
+                span.span,
+                msg,
+                vec![(
+                    first_span.span,
+                    format!("Order for language `{first_name}`."),
+                )],
+            );
+        }
+    }
+
+    fn unused_names(&mut self) {
+        let langs: HashSet<_> = self.scopes[1..]
+            .iter()
+            .filter_map(|s| s.lang.map(|l| self.lang_name(l)))
+            .collect();
+
+        let entries = mem::take(&mut self.scopes[0].name_entries);
+        for e in &entries {
+            let lang = self.lang_name(&e.lang);
+            if !langs.contains(&lang) {
+                self.report(
+                    Lint::UnusedName,
+                    e.lang.span,
+                    format!(
+                        "There is no `#language` block for language `{lang}` \
+                        so the name is never used."
+                    ),
+                );
+            }
+        }
+        self.scopes[0].name_entries = entries;
+    }
+
+    fn empty_verses(&mut self) {
+        let scopes = mem::take(&mut self.scopes);
+        for (ident, terms) in scopes.iter().flat_map(|s| &s.verses) {
+            let empty = terms.iter().all(|t| {
+                t.tok.kind == Token::LineString
+                    && decode_line_string(self.tree.text(&t.tok)).is_empty()
+            });
+            if empty {
+                let name = self.tree.text(ident);
+                self.report(
+                    Lint::EmptyVerse,
+                    ident.span,
+                    format!("Verse `{name}` has no lines."),
+                );
+            }
+        }
+        self.scopes = scopes;
+    }
+
+    fn typography(&mut self) {
+        if self.conf.severity(Lint::Typography) == Severity::Allow {
+            return;
+        }
+
+        let scopes = mem::take(&mut self.scopes);
+        let rules: Vec<_> = scopes
+            .iter()
+            .map(|s| s.lang.and_then(|l| rules_for(self.tree.text(l))))
+            .collect();
+
+        for (s, scope) in scopes.iter().enumerate() {
+            for (i, (_, terms)) in scope.verses.iter().enumerate() {
+                // Top level verses are checked with the rules of each
+                // language that uses them.
+                let users = match self.used.get(&(s, i)) {
+                    Some(u) if s == 0 => u.clone(),
+                    _ => vec![s],
+                };
+                for rules in users.iter().filter_map(|c| rules[*c]) {
+                    let lines = terms
+                        .iter()
+                        .filter(|t| t.tok.kind == Token::LineString);
+                    for t in lines {
+                        self.check_typography(rules, t);
+                    }
+                }
+            }
+        }
+        self.scopes = scopes;
+    }
+
+    fn check_typography(&mut self, rules: &LangRules, t: &Separated) {
+        let lines = decode_line_string(self.tree.text(&t.tok));
+        let Some((line, fixed)) = lines
+            .iter()
+            .map(|l| (l, rules.normalize(l)))
+            .find(|(l, n)| l != &n)
+        else {
+            return;
+        };
+        self.report(
+            Lint::Typography,
+            t.tok.span,
+            format!("Line `{line}` should be `{fixed}`."),
+        );
+    }
+
+    /// Scopes that produce configuration of the song.
+    fn configs(&self) -> Vec<usize> {
+        if self.scopes.len() == 1 {
+            vec![0]
+        } else {
+            (1..self.scopes.len()).collect()
+        }
+    }
+
+    /// Order used by the configuration. Language without order uses the top
+    /// level order. Redefinitions are reported separately and the first
+    /// order is used, the same as when the song is built.
+    fn order(&self, c: usize) -> Option<(&'a SyntaxToken, &'a [OrderEntry])> {
+        self.scopes[c]
+            .orders
+            .first()
+            .or_else(|| self.scopes[0].orders.first())
+            .copied()
+    }
+
+    /// Find the verse visible in the configuration. Verses are resolved when
+    /// the configuration is constructed, so language verses shadow the top
+    /// level verses. Redefinitions are reported separately and the first
+    /// definition is used, the same as when the song is built.
+    fn resolve(&self, c: usize, ident: &SyntaxToken) -> Option<VerseId> {
+        let name = self.tree.text(ident);
+        [c, 0].into_iter().find_map(|s| {
+            self.scopes[s]
+                .verses
+                .iter()
+                .position(|(i, _)| self.tree.text(i) == name)
+                .map(|i| (s, i))
+        })
+    }
+
+    /// Report language tag that is not valid BCP 47 tag.
+    fn check_lang_tag(&mut self, lang: &SyntaxToken) {
+        if let Err(e) = normalize_lang_tag(self.tree.text(lang)) {
+            self.report(Lint::ParseError, lang.span, e.to_string());
+        }
+    }
+
+    /// Normalized language tag, so that tags that differ only in case are
+    /// the same.
+    fn lang_name(&self, lang: &SyntaxToken) -> String {
+        let text = self.tree.text(lang);
+        normalize_lang_tag(text).unwrap_or_else(|_| text.to_string())
+    }
+
+    fn undefined(&mut self, c: usize, ident: &SyntaxToken) {
+        let name = self.tree.text(ident);
+        let msg = if let Some(lang) = self.scopes[c].lang {
+            let lang = self.tree.text(lang);
+            format!("Verse `{name}` is not defined for language `{lang}`.")
+        } else {
+            format!("Verse `{name}` is not defined.")
+        };
+        self.report(Lint::UndefinedVerse, ident.span, msg);
+    }
+
+    fn redefinition(
+        &mut self,
+        tok: &SyntaxToken,
+        prev: &SyntaxToken,
+        msg: String,
+    ) {
+        self.report_with(
+            Lint::Redefinition,
+            tok.span,
+            msg,
+            vec![(prev.span, "Previous definition is here.".into())],
+        );
+    }
+
+    fn report(&mut self, lint: Lint, span: Span, message: String) {
+        self.report_with(lint, span, message, vec![]);
+    }
+
+    fn report_with(
+        &mut self,
+        lint: Lint,
+        span: Span,
+        message: String,
+        notes: Vec<(Span, String)>,
+    ) {
+        let severity = self.conf.severity(lint);
+        if severity == Severity::Allow || !self.reported.insert((lint, span)) {
+            return;
+        }
+        self.res.push(Diagnostic {
+            lint,
+            severity,
+            span,
+            message,
+            notes,
+        });
+    }
+
+    fn scope(&mut self) -> &mut Scope<'a> {
+        self.scopes.last_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lints::{Lint, LintConf, LintSel, Severity, lint_str},
+        syntax::Pos,
+    };
+
+    /// Codes of the diagnostics with the lines where they are.
+    fn lint(src: &str) -> Vec<(&'static str, usize)> {
+        lint_with(src, &LintConf::default())
+    }
+
+    fn lint_with(src: &str, conf: &LintConf) -> Vec<(&'static str, usize)> {
+        lint_str(src, conf)
+            .into_iter()
+            .map(|d| (d.lint.code(), Pos::at(src, d.span.start).line))
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_verse() {
+        let src = "v1: l{ a }\nv1: l{ b }\norder = [v1]\n";
+        assert_eq!(lint(src), [("E004", 2)]);
+    }
+
+    #[test]
+    fn duplicate_order() {
+        let src = "v1: l{ a }\nv3: l{ c }\norder = [v1, v4]\n\
+            order = [v3]\n";
+        assert_eq!(lint(src), [("W001", 2), ("E002", 3), ("E004", 4)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(lint("v1: l{ a }\norder = [v1\n")[0].0, "E001");
+        let src = "v1: l{ a }\nname = \"Song\"\n#language xx-\norder = [v1]\n";
+        assert_eq!(lint(src), [("E001", 2), ("E001", 3)]);
+    }
+
+    #[test]
+    fn undefined_verse() {
+        let src = "v1: l{ a } + v2\norder = [v1, v3]\n";
+        assert_eq!(lint(src), [("E002", 1), ("E002", 2)]);
+        let src = "#language cs\nv1: l{ a }\norder = [v1]\n\
+            #language en\norder = [v1]\n";
+        assert_eq!(lint(src), [("E002", 5)]);
+    }
+
+    #[test]
+    fn cyclic_verse() {
+        let src = "v1: l{ a } + v2\nv2: v1\norder = [v1]\n";
+        assert_eq!(lint(src), [("E003", 2)]);
+    }
+
+    #[test]
+    fn unused_verse() {
+        let src = "v1: l{ a }\nv2: l{ b }\norder = [v1]\n";
+        assert_eq!(lint(src), [("W001", 2)]);
+    }
+
+    #[test]
+    fn order_length() {
+        let src = "v1: l{ a }\norder = [v1]\n\
+            #language cs\n\
+            #language en\norder = [v1, v1]\n";
+        assert_eq!(lint(src), [("W002", 5)]);
+    }
+
+    #[test]
+    fn unused_name() {
+        let src = "name = { cs: \"Píseň\", en: \"Song\" }\nv1: l{ a }\n\
+            #language cs\norder = [v1]\n";
+        assert_eq!(lint(src), [("W003", 1)]);
+    }
+
+    #[test]
+    fn empty_verse() {
+        let src = "v1: l{ a } + v2\nv2: l{ }\norder = [v1]\n";
+        assert_eq!(lint(src), [("W004", 2)]);
+    }
+
+    #[test]
+    fn shadowed_verse() {
+        let src = "v1: l{ a }\n#language cs\nv1: l{ b }\norder = [v1]\n";
+        // Shadowing is allowed by default.
+        assert_eq!(lint(src), [("W001", 1)]);
+        let mut conf = LintConf::default();
+        conf.set(LintSel::One(Lint::ShadowedVerse), Severity::Warning);
+        assert_eq!(lint_with(src, &conf), [("W001", 1), ("W007", 3)]);
+    }
+
+    #[test]
+    fn lint_levels() {
+        let src = "v1: l{ a }\nv2: l{ b }\norder = [v1, v3]\n";
+        let mut conf = LintConf::default();
+        conf.set(LintSel::One(Lint::UnusedVerse), Severity::Allow);
+        assert_eq!(lint_with(src, &conf), [("E002", 3)]);
+        conf.set(LintSel::One(Lint::UnusedVerse), Severity::Error);
+        conf.set(LintSel::One(Lint::UndefinedVerse), Severity::Warning);
+        let sev: Vec<_> =
+            lint_str(src, &conf).iter().map(|d| d.severity).collect();
+        // Severity of errors cannot be changed.
+        assert_eq!(sev, [Severity::Error, Severity::Error]);
+    }
+
+    #[test]
+    fn typography_of_top_level_verses() {
+        let mut conf = LintConf::default();
+        conf.set(LintSel::One(Lint::Typography), Severity::Warning);
+        // The languages have different rules and both of them use `v1`.
+        let src = "v1: l{ say \"hi\" }\nv2: l{ ok }\n\
+            #language cs\norder = [v1, v2]\n\
+            #language en\norder = [v1, v2]\n";
+        assert_eq!(lint_with(src, &conf), [("W006", 1)]);
+        let src = "v1: l{ „ahoj“ }\n#language cs\norder = [v1]\n";
+        assert_eq!(lint_with(src, &conf), []);
+    }
+}
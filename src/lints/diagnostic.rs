@@ -0,0 +1,14 @@
+use crate::{
+    lints::{Lint, Severity},
+    syntax::Span,
+};
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub lint: Lint,
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    /// Additional locations related to the problem.
+    pub notes: Vec<(Span, String)>,
+}
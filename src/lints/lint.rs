@@ -0,0 +1,129 @@
+use crate::lints::Severity;
+
+/// Kinds of problems reported by the lint pass. Codes and names of the lints
+/// are stable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    ParseError,
+    UndefinedVerse,
+    CyclicVerse,
+    UnusedVerse,
+    OrderLength,
+    UnusedName,
+    Redefinition,
+    EmptyVerse,
+    ShadowedVerse,
+    Typography,
+}
+
+impl Lint {
+    pub const ALL: [Self; 10] = [
+        Self::ParseError,
+        Self::UndefinedVerse,
+        Self::CyclicVerse,
+        Self::Redefinition,
+        Self::UnusedVerse,
+        Self::OrderLength,
+        Self::UnusedName,
+        Self::EmptyVerse,
+        Self::Typography,
+        Self::ShadowedVerse,
+    ];
+
+    /// Codes are never reused. `W005` was the redefinition warning before
+    /// it became the error `E004`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::ParseError => "E001",
+            Self::UndefinedVerse => "E002",
+            Self::CyclicVerse => "E003",
+            Self::Redefinition => "E004",
+            Self::UnusedVerse => "W001",
+            Self::OrderLength => "W002",
+            Self::UnusedName => "W003",
+            Self::EmptyVerse => "W004",
+            Self::Typography => "W006",
+            Self::ShadowedVerse => "W007",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ParseError => "parse-error",
+            Self::UndefinedVerse => "undefined-verse",
+            Self::CyclicVerse => "cyclic-verse",
+            Self::UnusedVerse => "unused-verse",
+            Self::OrderLength => "order-length",
+            Self::UnusedName => "unused-name",
+            Self::EmptyVerse => "empty-verse",
+            Self::Redefinition => "redefinition",
+            Self::ShadowedVerse => "shadowed-verse",
+            Self::Typography => "typography",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::ParseError => "The song cannot be parsed.",
+            Self::UndefinedVerse => {
+                "Verse used in `order` or in expression is not defined."
+            }
+            Self::CyclicVerse => "Verse depends on itself.",
+            Self::UnusedVerse => "Verse is defined but never used.",
+            Self::OrderLength => {
+                "Languages have orders with different number of verses."
+            }
+            Self::UnusedName => {
+                "Name is given for language without \
+                `#language` block."
+            }
+            Self::EmptyVerse => "Verse has no lines.",
+            Self::Redefinition => {
+                "Verse, language, name or order is defined multiple times in \
+                the same scope."
+            }
+            Self::ShadowedVerse => {
+                "Verse in `#language` block shadows verse from the top level."
+            }
+            Self::Typography => {
+                "Quotes, apostrophes, dashes or ellipses don't have the \
+                typographic form of the language. This is what \
+                `--smart-quotes` changes."
+            }
+        }
+    }
+
+    pub fn default_severity(self) -> Severity {
+        if self.is_fixed() {
+            Severity::Error
+        } else if self == Self::ShadowedVerse {
+            // Shadowing is intentional way to override verse for single
+            // language.
+            Severity::Allow
+        } else if self == Self::Typography {
+            // Typography is opt-in.
+            Severity::Allow
+        } else {
+            Severity::Warning
+        }
+    }
+
+    /// Lints that make the song invalid. Their severity cannot be changed.
+    pub fn is_fixed(self) -> bool {
+        matches!(
+            self,
+            Self::ParseError
+                | Self::UndefinedVerse
+                | Self::CyclicVerse
+                | Self::Redefinition
+        )
+    }
+
+    /// Find lint by its code or name.
+    pub fn find(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| {
+            l.code().eq_ignore_ascii_case(s)
+                || l.name().eq_ignore_ascii_case(s)
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use pareg::{ArgError, FromArg};
+
+use crate::lints::{Lint, Severity};
+
+/// Selection of lints whose severity is changed.
+#[derive(Debug, Clone, Copy)]
+pub enum LintSel {
+    All,
+    /// All lints that are warnings by default.
+    Warnings,
+    One(Lint),
+}
+
+/// Configuration of the severity of lints.
+#[derive(Debug, Default, Clone)]
+pub struct LintConf {
+    /// Changes of severity. Later changes override the previous.
+    levels: Vec<(LintSel, Severity)>,
+}
+
+impl LintSel {
+    fn contains(self, lint: Lint) -> bool {
+        match self {
+            Self::All => true,
+            Self::Warnings => lint.default_severity() == Severity::Warning,
+            Self::One(l) => l == lint,
+        }
+    }
+}
+
+impl<'a> FromArg<'a> for LintSel {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        match arg {
+            "all" => Ok(Self::All),
+            "warnings" => Ok(Self::Warnings),
+            s => Lint::find(s).map(Self::One).ok_or_else(|| {
+                ArgError::parse_msg("Unknown lint.", s.to_string()).hint(
+                    "Use `wohu check --list-lints` to see all the lints.",
+                )
+            }),
+        }
+    }
+}
+
+impl LintConf {
+    pub fn set(&mut self, sel: LintSel, severity: Severity) {
+        self.levels.push((sel, severity));
+    }
+
+    pub fn severity(&self, lint: Lint) -> Severity {
+        if lint.is_fixed() {
+            return lint.default_severity();
+        }
+
+        self.levels
+            .iter()
+            .rev()
+            .find(|(s, _)| s.contains(lint))
+            .map(|(_, s)| *s)
+            .unwrap_or(lint.default_severity())
+    }
+}
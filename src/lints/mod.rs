@@ -0,0 +1,61 @@
+//! Problems in wohu sources that are not syntax errors, such as undefined
+//! or unused verses.
+
+mod checker;
+mod diagnostic;
+mod lint;
+mod lint_conf;
+mod severity;
+
+use crate::{
+    parse::{ParseErrors, parse_str},
+    syntax::{Span, SyntaxError, parse_syntax},
+};
+
+use self::checker::Checker;
+
+pub use self::{diagnostic::*, lint::*, lint_conf::*, severity::*};
+
+/// Find all problems in the song source.
+pub fn lint_str(src: &str, conf: &LintConf) -> Vec<Diagnostic> {
+    let tree = match parse_syntax(src) {
+        Ok(t) => t,
+        Err(e) => {
+            let (span, message) = match e.downcast::<SyntaxError>() {
+                Ok(e) => (e.span, e.msg),
+                Err(e) => (Span::default(), e.to_string()),
+            };
+            return vec![Diagnostic {
+                lint: Lint::ParseError,
+                severity: Severity::Error,
+                span,
+                message,
+                notes: vec![],
+            }];
+        }
+    };
+
+    let mut res = Checker::new(&tree, conf).check();
+
+    // Problems not found by the lints are still reported by the parser.
+    if !res.iter().any(|d| d.severity == Severity::Error)
+        && let Err(e) = parse_str(src)
+    {
+        let errs = match e.downcast::<ParseErrors>() {
+            Ok(ParseErrors(errs)) => errs
+                .into_iter()
+                .map(|e| (e.pos.offset(src), e.msg))
+                .collect(),
+            Err(e) => vec![(0, e.to_string())],
+        };
+        res.extend(errs.into_iter().map(|(o, message)| Diagnostic {
+            lint: Lint::ParseError,
+            severity: Severity::Error,
+            span: Span::new(o, o),
+            message,
+            notes: vec![],
+        }));
+    }
+
+    res
+}
@@ -0,0 +1,18 @@
+use pareg::FromArg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, FromArg)]
+pub enum Severity {
+    Allow,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
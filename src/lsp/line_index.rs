@@ -0,0 +1,60 @@
+use serde_json::{Value, json};
+use wohu::syntax::Span;
+
+/// Conversion between byte offsets and LSP positions. LSP columns are
+/// counted in UTF-16 code units.
+pub struct LineIndex<'a> {
+    src: &'a str,
+    /// Offsets of the starts of lines.
+    lines: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(src: &'a str) -> Self {
+        let lines = [0]
+            .into_iter()
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { src, lines }
+    }
+
+    pub fn position(&self, offset: usize) -> Value {
+        let line = self.lines.partition_point(|s| *s <= offset) - 1;
+        let col: usize = self.src[self.lines[line]..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        json!({ "line": line, "character": col })
+    }
+
+    pub fn range(&self, span: Span) -> Value {
+        json!({
+            "start": self.position(span.start),
+            "end": self.position(span.end),
+        })
+    }
+
+    /// Range of the whole source.
+    pub fn full_range(&self) -> Value {
+        self.range(Span::new(0, self.src.len()))
+    }
+
+    /// Get offset from LSP position. Positions out of the source are
+    /// clamped.
+    pub fn offset(&self, pos: &Value) -> usize {
+        let line = pos["line"].as_u64().unwrap_or_default() as usize;
+        let col = pos["character"].as_u64().unwrap_or_default() as usize;
+        let Some(start) = self.lines.get(line).copied() else {
+            return self.src.len();
+        };
+
+        let mut units = 0;
+        for (i, c) in self.src[start..].char_indices() {
+            if units >= col || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        self.src.len()
+    }
+}
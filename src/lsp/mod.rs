@@ -0,0 +1,6 @@
+mod line_index;
+mod server;
+mod symbols;
+mod transport;
+
+pub use self::server::*;
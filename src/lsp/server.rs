@@ -0,0 +1,379 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use wohu::{
+    lints::{LintConf, Severity, lint_str},
+    source_fmt::format_str,
+    syntax::{SyntaxTree, is_ident_continue, is_ident_start, parse_syntax},
+};
+
+use crate::{
+    cli::ConfigSource,
+    lsp::{
+        line_index::LineIndex,
+        symbols::Symbols,
+        transport::{read_message, write_message},
+    },
+};
+
+/// JSON-RPC error codes.
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Completion item kinds.
+const KIND_VARIABLE: u8 = 6;
+const KIND_CONSTANT: u8 = 21;
+
+/// Language server for wohu sources. Documents are synchronized as whole.
+pub struct Server<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Open documents by their uri.
+    docs: HashMap<String, Document>,
+    config: ConfigSource,
+    /// Lint levels from the configuration.
+    lints: LintConf,
+    shutdown: bool,
+}
+
+/// Open document.
+struct Document {
+    text: String,
+    /// Syntax tree of the last version of the document without syntax
+    /// errors. Documents are mostly incomplete while they are edited, so
+    /// the features work with the last valid version.
+    tree: Option<SyntaxTree>,
+}
+
+impl Document {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            tree: parse_syntax(text).ok(),
+        }
+    }
+
+    fn update(&mut self, text: &str) {
+        self.text = text.to_string();
+        if let Ok(tree) = parse_syntax(text) {
+            self.tree = Some(tree);
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Server<R, W> {
+    pub fn new(input: R, output: W, config: ConfigSource) -> Self {
+        Self {
+            input,
+            output,
+            docs: HashMap::new(),
+            config,
+            lints: LintConf::default(),
+            shutdown: false,
+        }
+    }
+
+    /// Serve the requests until the client tells the server to exit.
+    pub fn run(mut self) -> Result<ExitCode> {
+        while let Some(msg) = read_message(&mut self.input)? {
+            let Some(method) = msg["method"].as_str() else {
+                // Responses to requests from server are not used.
+                continue;
+            };
+            let params = &msg["params"];
+
+            if let Some(id) = msg.get("id") {
+                let res = match self.request(method, params) {
+                    Ok(res) => json!({ "id": id, "result": res }),
+                    Err((code, message)) => json!({
+                        "id": id,
+                        "error": { "code": code, "message": message },
+                    }),
+                };
+                self.send(res)?;
+            } else if method == "exit" {
+                return Ok(if self.shutdown {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::FAILURE
+                });
+            } else {
+                self.notification(method, params)?;
+            }
+        }
+
+        Ok(ExitCode::FAILURE)
+    }
+
+    fn request(
+        &mut self,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, (i64, String)> {
+        let res = match method {
+            "initialize" => {
+                self.load_config(params);
+                Some(initialize())
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                None
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/rename" => self.rename(params)?,
+            "textDocument/formatting" => self.formatting(params),
+            m => {
+                return Err((
+                    METHOD_NOT_FOUND,
+                    format!("Unsupported method `{m}`."),
+                ));
+            }
+        };
+        Ok(res.unwrap_or(Value::Null))
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Result<()> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "textDocument/didOpen" => {
+                let text = &params["textDocument"]["text"];
+                let text = text.as_str().unwrap_or_default();
+                self.docs.insert(uri.to_string(), Document::new(text));
+                self.publish_diagnostics(uri)
+            }
+            "textDocument/didChange" => {
+                // With full synchronization, the last change is the whole
+                // document.
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes
+                    .and_then(|c| c.last())
+                    .and_then(|c| c["text"].as_str())
+                else {
+                    return Ok(());
+                };
+                let Some(doc) = self.docs.get_mut(uri) else {
+                    return Ok(());
+                };
+                doc.update(text);
+                self.publish_diagnostics(uri)
+            }
+            "textDocument/didClose" => {
+                self.docs.remove(uri);
+                self.send(json!({
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> Result<()> {
+        let src = &self.docs[uri].text;
+        let idx = LineIndex::new(src);
+        let diags: Vec<_> = lint_str(src, &self.lints)
+            .into_iter()
+            .filter(|d| d.severity != Severity::Allow)
+            .map(|d| {
+                let notes: Vec<_> = d
+                    .notes
+                    .iter()
+                    .map(|(s, m)| {
+                        json!({
+                            "location": { "uri": uri, "range": idx.range(*s) },
+                            "message": m,
+                        })
+                    })
+                    .collect();
+                let severity =
+                    if d.severity == Severity::Error { 1 } else { 2 };
+                json!({
+                    "range": idx.range(d.span),
+                    "severity": severity,
+                    "code": d.lint.code(),
+                    "source": "wohu",
+                    "message": d.message,
+                    "relatedInformation": notes,
+                })
+            })
+            .collect();
+
+        self.send(json!({
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diags },
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, tree) = self.tree(params)?;
+        let syms = Symbols::new(tree);
+        let idx = LineIndex::new(&tree.src);
+
+        let occ = syms.at(idx.offset(&params["position"]))?;
+        let span = syms.definition(syms.symbol(occ)?)?;
+        Some(json!({ "uri": uri, "range": idx.range(span) }))
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (_, tree) = self.tree(params)?;
+        let syms = Symbols::new(tree);
+        let idx = LineIndex::new(&tree.src);
+
+        let occ = syms.at(idx.offset(&params["position"]))?;
+        let lines = syms.lines(syms.symbol(occ)?, occ.scope)?;
+        Some(json!({
+            "contents": { "kind": "plaintext", "value": lines.join("\n") },
+            "range": idx.range(occ.span),
+        }))
+    }
+
+    fn completion(&self, params: &Value) -> Option<Value> {
+        let (_, tree) = self.tree(params)?;
+        let syms = Symbols::new(tree);
+        let idx = LineIndex::new(&tree.src);
+
+        let scope = syms.scope_at(idx.offset(&params["position"]));
+        let verses = syms.visible(scope).into_iter().map(|v| {
+            json!({ "label": v, "kind": KIND_VARIABLE, "detail": "verse" })
+        });
+        let langs = syms.languages().iter().map(|l| {
+            json!({ "label": l, "kind": KIND_CONSTANT, "detail": "language" })
+        });
+        Some(Value::Array(verses.chain(langs).collect()))
+    }
+
+    fn rename(&self, params: &Value) -> Result<Option<Value>, (i64, String)> {
+        let name = params["newName"].as_str().unwrap_or_default();
+        if !is_ident(name) {
+            return Err((
+                INVALID_PARAMS,
+                format!("`{name}` is not valid verse identifier."),
+            ));
+        }
+
+        // Renaming with older version of the document would change wrong
+        // places.
+        let Some((uri, tree)) = self
+            .tree(params)
+            .filter(|(uri, t)| self.docs[*uri].text == t.src)
+        else {
+            return Ok(None);
+        };
+        let syms = Symbols::new(tree);
+        let idx = LineIndex::new(&tree.src);
+
+        let Some(sym) = syms
+            .at(idx.offset(&params["position"]))
+            .and_then(|o| syms.symbol(o))
+        else {
+            return Ok(None);
+        };
+        let edits: Vec<_> = syms
+            .references(sym)
+            .into_iter()
+            .map(|s| json!({ "range": idx.range(s), "newText": name }))
+            .collect();
+        Ok(Some(json!({ "changes": { uri: edits } })))
+    }
+
+    fn formatting(&self, params: &Value) -> Option<Value> {
+        let (_, src) = self.doc(params)?;
+        let formatted = format_str(src).ok()?;
+        let range = LineIndex::new(src).full_range();
+        Some(json!([{ "range": range, "newText": formatted }]))
+    }
+
+    /// Load the lint levels from the configuration of the workspace. Errors
+    /// are logged to stderr and the default levels are used, so that the
+    /// other features work.
+    fn load_config(&mut self, params: &Value) {
+        let root = params["rootUri"]
+            .as_str()
+            .and_then(uri_path)
+            .or_else(|| params["rootPath"].as_str().map(PathBuf::from));
+        let conf = match root {
+            Some(r) => self.config.load_in(&r),
+            None => self.config.load(),
+        };
+        match conf {
+            Ok(c) => self.lints = c.lint_conf(),
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+
+    /// Get uri and source of the document from the request parameters.
+    fn doc<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a str)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        Some((uri, &self.docs.get(uri)?.text))
+    }
+
+    /// Get uri and the last valid syntax tree of the document from the
+    /// request parameters.
+    fn tree<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Option<(&'a str, &'a SyntaxTree)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        Some((uri, self.docs.get(uri)?.tree.as_ref()?))
+    }
+
+    fn send(&mut self, mut msg: Value) -> Result<()> {
+        msg["jsonrpc"] = "2.0".into();
+        write_message(&mut self.output, &msg)
+    }
+}
+
+fn initialize() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "definitionProvider": true,
+            "hoverProvider": true,
+            "completionProvider": {},
+            "renameProvider": true,
+            "documentFormattingProvider": true,
+        },
+        "serverInfo": {
+            "name": "wohu",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// Get path from `file` uri.
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?.as_bytes();
+    let mut res = vec![];
+    let mut i = 0;
+    while i < path.len() {
+        let hex = path.get(i + 1..i + 3).and_then(|h| {
+            u8::from_str_radix(str::from_utf8(h).ok()?, 16).ok()
+        });
+        match (path[i], hex) {
+            (b'%', Some(b)) => {
+                res.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                res.push(b);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(res).ok()?))
+}
+
+/// Check that the verse identifier can be written as is.
+fn is_ident(name: &str) -> bool {
+    name.starts_with(is_ident_start)
+        && name.chars().all(is_ident_continue)
+        && !matches!(name, "name" | "order" | "note")
+}
@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+
+use wohu::syntax::{
+    Item, Separated, Span, SyntaxTree, Token, decode_line_string,
+};
+
+/// Verse identified by its scope and name. Scope 0 is the top level and each
+/// `#language` block has its own scope.
+pub type Symbol<'a> = (usize, &'a str);
+
+/// Occurrence of verse identifier in the source.
+#[derive(Debug, Clone, Copy)]
+pub struct Occurrence<'a> {
+    pub span: Span,
+    pub name: &'a str,
+    pub scope: usize,
+    /// Whether this is definition of the verse.
+    pub def: bool,
+}
+
+/// Verse definitions and references in the syntax tree.
+pub struct Symbols<'a> {
+    tree: &'a SyntaxTree,
+    /// Start of each scope.
+    scopes: Vec<usize>,
+    occurrences: Vec<Occurrence<'a>>,
+    verses: Vec<(Symbol<'a>, &'a [Separated])>,
+    languages: BTreeSet<&'a str>,
+}
+
+impl<'a> Symbols<'a> {
+    pub fn new(tree: &'a SyntaxTree) -> Self {
+        let mut res = Self {
+            tree,
+            scopes: vec![0],
+            occurrences: vec![],
+            verses: vec![],
+            languages: BTreeSet::new(),
+        };
+
+        for item in &tree.items {
+            let scope = res.scopes.len() - 1;
+            let mut occurrence = |span, def| {
+                res.occurrences.push(Occurrence {
+                    span,
+                    name: span.text(&tree.src),
+                    scope,
+                    def,
+                })
+            };
+            match item {
+                Item::Language { decl, lang } => {
+                    res.scopes.push(decl.span.start);
+                    res.languages.insert(tree.text(lang));
+                }
+                Item::Names { entries, .. } => res
+                    .languages
+                    .extend(entries.iter().map(|e| tree.text(&e.lang))),
+                Item::Order { entries, .. } => {
+                    for e in entries {
+                        occurrence(e.tok.span, false);
+                    }
+                }
+                Item::Verse { ident, terms, .. } => {
+                    occurrence(ident.span, true);
+                    for t in
+                        terms.iter().filter(|t| t.tok.kind == Token::Ident)
+                    {
+                        occurrence(t.tok.span, false);
+                    }
+                    res.verses.push(((scope, tree.text(ident)), terms));
+                }
+                Item::Name { .. } | Item::Note { .. } => {}
+            }
+        }
+
+        res
+    }
+
+    /// Find verse identifier at the given offset.
+    pub fn at(&self, offset: usize) -> Option<Occurrence<'a>> {
+        self.occurrences
+            .iter()
+            .find(|o| o.span.start <= offset && offset <= o.span.end)
+            .copied()
+    }
+
+    /// Get the verse that the occurrence refers to.
+    pub fn symbol(&self, occ: Occurrence<'a>) -> Option<Symbol<'a>> {
+        if occ.def {
+            Some((occ.scope, occ.name))
+        } else {
+            self.resolve(occ.scope, occ.name)
+        }
+    }
+
+    /// Span of the definition of the verse. Later redefinitions are ignored,
+    /// the same as when the song is built.
+    pub fn definition(&self, sym: Symbol<'a>) -> Option<Span> {
+        self.occurrences
+            .iter()
+            .find(|o| o.def && (o.scope, o.name) == sym)
+            .map(|o| o.span)
+    }
+
+    /// All the definitions and references of the verse.
+    pub fn references(&self, sym: Symbol<'a>) -> Vec<Span> {
+        self.occurrences
+            .iter()
+            .filter(|o| self.symbol(**o) == Some(sym))
+            .map(|o| o.span)
+            .collect()
+    }
+
+    /// Evaluate lines of the verse as they are for the language of the
+    /// scope. Returns [`None`] if the verse cannot be evaluated.
+    pub fn lines(&self, sym: Symbol<'a>, scope: usize) -> Option<Vec<String>> {
+        let mut res = vec![];
+        self.eval(sym, scope, &mut vec![], &mut res).then_some(res)
+    }
+
+    /// Index of the scope that contains the offset.
+    pub fn scope_at(&self, offset: usize) -> usize {
+        self.scopes.partition_point(|s| *s <= offset).max(1) - 1
+    }
+
+    /// Names of the verses visible in the scope.
+    pub fn visible(&self, scope: usize) -> BTreeSet<&'a str> {
+        self.verses
+            .iter()
+            .filter(|((s, _), _)| *s == scope || *s == 0)
+            .map(|((_, n), _)| *n)
+            .collect()
+    }
+
+    /// Names of all the languages used in the source.
+    pub fn languages(&self) -> &BTreeSet<&'a str> {
+        &self.languages
+    }
+
+    /// Verses are resolved in the scope of the language and then in the
+    /// top level.
+    fn resolve(&self, scope: usize, name: &'a str) -> Option<Symbol<'a>> {
+        [scope, 0]
+            .into_iter()
+            .map(|s| (s, name))
+            .find(|sym| self.verses.iter().any(|(v, _)| v == sym))
+    }
+
+    fn eval(
+        &self,
+        sym: Symbol<'a>,
+        scope: usize,
+        stack: &mut Vec<Symbol<'a>>,
+        res: &mut Vec<String>,
+    ) -> bool {
+        if stack.contains(&sym) {
+            return false;
+        }
+        let Some((_, terms)) = self.verses.iter().find(|(v, _)| *v == sym)
+        else {
+            return false;
+        };
+
+        stack.push(sym);
+        for t in *terms {
+            let text = self.tree.text(&t.tok);
+            if t.tok.kind == Token::LineString {
+                res.extend(decode_line_string(text));
+                continue;
+            }
+            let Some(s) = self.resolve(scope, text) else {
+                return false;
+            };
+            if !self.eval(s, scope, stack, res) {
+                return false;
+            }
+        }
+        stack.pop();
+        true
+    }
+}
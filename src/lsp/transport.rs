@@ -0,0 +1,41 @@
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+/// Read single JSON-RPC message. Returns [`None`] at the end of the input.
+pub fn read_message(r: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut len = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if r.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            len = Some(value.trim().parse::<usize>()?);
+        }
+    }
+
+    let Some(len) = len else {
+        bail!("Missing the header `Content-Length`.");
+    };
+    let mut body = vec![0; len];
+    r.read_exact(&mut body)?;
+    let msg = serde_json::from_slice(&body).context("Invalid message.")?;
+    Ok(Some(msg))
+}
+
+/// Write single JSON-RPC message.
+pub fn write_message(w: &mut impl Write, msg: &Value) -> Result<()> {
+    let body = msg.to_string();
+    write!(w, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    w.flush()?;
+    Ok(())
+}
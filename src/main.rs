@@ -1,26 +1,33 @@
-use std::{
-    fs::File,
-    io::{BufWriter, Write, stdout},
-    process::ExitCode,
-};
+use std::process::ExitCode;
 
 use anyhow::Result;
 use pareg::Pareg;
 
-use crate::{
-    cli::Args,
-    out_fmt::{FmtType, OutFmt},
-    parse::parse_file,
-};
+use crate::cli::{Args, Command, ConfigError, help};
 
 mod cli;
-mod data;
-mod out_fmt;
-mod parse;
+mod cmd;
+mod lsp;
+mod present;
+
+/// Exit code used when the command line arguments are invalid.
+const USAGE_ERROR: u8 = 2;
 
 fn main() -> ExitCode {
-    match start() {
-        Ok(_) => ExitCode::SUCCESS,
+    let args = match Args::parse(Pareg::args()) {
+        Ok(a) => a,
+        Err(e) if e.is::<ConfigError>() => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+
+    match start(args) {
+        Ok(code) => code,
         Err(e) => {
             eprintln!("error: {e}");
             ExitCode::FAILURE
@@ -28,45 +35,26 @@ fn main() -> ExitCode {
     }
 }
 
-fn start() -> Result<()> {
-    let args = Args::parse(Pareg::args())?;
-
-    let mut outputs: Vec<Box<dyn OutFmt>> = vec![];
-    for (t, o) in args.outputs {
-        let out: Box<dyn Write> = if let Some(f) = o {
-            Box::new(BufWriter::new(File::create(f)?))
-        } else {
-            Box::new(stdout().lock())
-        };
-
-        let of: Box<dyn OutFmt> = match t {
-            FmtType::Text => Box::new(out_fmt::Text::new(out)),
-            FmtType::LatexSlides => Box::new(out_fmt::Latex::new(out)),
-        };
-        outputs.push(of);
-    }
-
-    for o in &mut outputs {
-        o.init()?;
-    }
-    for (i, ip) in args.input.iter().enumerate() {
-        let s = parse_file(ip)?;
-        for o in &mut outputs {
-            if i != 0 {
-                o.song_space()?;
-            }
-            let cfg = s.configs.get(&s.default).unwrap();
-            for (i, v) in cfg.verses.iter().enumerate() {
-                if i != 0 {
-                    o.verse_space()?;
-                }
-                o.write_verse(v.as_slice())?;
-            }
+fn start(args: Args) -> Result<ExitCode> {
+    match args.command {
+        Command::Help(cmd) => {
+            print!("{}", help::help(cmd));
+            Ok(ExitCode::SUCCESS)
         }
+        Command::Version => {
+            println!("{}", help::version());
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Completions(a) => cmd::completions(a),
+        Command::Build(a) => cmd::build(a),
+        Command::Check(a) => cmd::check(a),
+        Command::Fmt(a) => cmd::fmt(a),
+        Command::List(a) => cmd::list(a),
+        Command::Import(a) => cmd::import(a),
+        Command::Lsp(a) => cmd::lsp(a),
+        Command::Present(a) => cmd::present(a),
+        Command::Display(a) => cmd::display(a),
+        Command::Serve(a) => cmd::serve(a),
+        Command::Config(a) => cmd::config(a),
     }
-    for o in &mut outputs {
-        o.finalize()?;
-    }
-
-    Ok(())
 }
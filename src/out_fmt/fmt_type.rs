@@ -4,5 +4,51 @@ use pareg::FromArg;
 pub enum FmtType {
     #[default]
     Text,
+    #[arg("latex-slides" | "tex-slides")]
     LatexSlides,
+    #[arg("latex-stage" | "tex-stage")]
+    LatexStage,
+    #[arg("html-overlay" | "overlay")]
+    HtmlOverlay,
+}
+
+impl FmtType {
+    pub const ALL: [Self; 4] = [
+        Self::Text,
+        Self::LatexSlides,
+        Self::LatexStage,
+        Self::HtmlOverlay,
+    ];
+
+    /// Names of all the formats as accepted by `--format`.
+    pub const NAMES: [&str; 4] =
+        ["text", "latex-slides", "latex-stage", "html-overlay"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Text => Self::NAMES[0],
+            Self::LatexSlides => Self::NAMES[1],
+            Self::LatexStage => Self::NAMES[2],
+            Self::HtmlOverlay => Self::NAMES[3],
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Text => "Plain text with empty lines between verses.",
+            Self::LatexSlides => {
+                "LaTeX beamer presentation with one verse per slide."
+            }
+            Self::LatexStage => {
+                "LaTeX beamer stage display for the singers. Each slide \
+                shows the current and the next verse of the matching slide \
+                of `latex-slides`."
+            }
+            Self::HtmlOverlay => {
+                "Transparent HTML page with lyrics at the bottom for \
+                streaming. Step through the lines with arrows or by \
+                clicking."
+            }
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use std::{io::Write, mem};
+
+use crate::{
+    Line,
+    out_fmt::OutFmt,
+    typography::{self, LangRules},
+};
+
+const PAGE: &str = include_str!("overlay.html");
+/// Place in the page where the songs are inserted.
+const SONGS_MARK: &str = "/*SONGS*/null";
+/// Place in the page where the style is inserted.
+const STYLE_MARK: &str = "/*STYLE*/{}";
+
+/// Names of the style values of the overlay page. They are the same as the
+/// names of its query parameters.
+pub const OVERLAY_STYLE_NAMES: [&str; 9] = [
+    "font",
+    "size",
+    "color",
+    "outline",
+    "outline-color",
+    "shadow",
+    "shadow-color",
+    "bottom",
+    "lines",
+];
+
+/// Transparent HTML page with the lyrics at the bottom, for use as browser
+/// source in streaming software. The page steps through the verses one or
+/// two lines at a time.
+pub struct HtmlOverlay<W: Write> {
+    writer: W,
+    songs: Vec<Vec<Vec<String>>>,
+    rules: Option<&'static LangRules>,
+    style: Vec<(String, String)>,
+}
+
+impl<W: Write> HtmlOverlay<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            songs: vec![vec![]],
+            rules: None,
+            style: vec![],
+        }
+    }
+
+    /// Set the default style of the page as pairs of name from
+    /// [`OVERLAY_STYLE_NAMES`] and value. Query parameters of the page
+    /// still take precedence.
+    pub fn with_style(mut self, style: Vec<(String, String)>) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// The overlay page that shows the slides of live presentation. It
+/// receives the slides from the server through WebSocket at `/ws`. See
+/// [`HtmlOverlay::with_style`] for `style`.
+pub fn live_overlay_page(style: &[(String, String)]) -> String {
+    page("null", style)
+}
+
+fn page(songs: &str, style: &[(String, String)]) -> String {
+    let style: serde_json::Map<_, _> = style
+        .iter()
+        .map(|(k, v)| (k.clone(), v.as_str().into()))
+        .collect();
+    // The values must not end the script.
+    let style = serde_json::Value::from(style)
+        .to_string()
+        .replace("</", "<\\/");
+    PAGE.replace(STYLE_MARK, &style).replace(SONGS_MARK, songs)
+}
+
+impl<W: Write> OutFmt for HtmlOverlay<W> {
+    fn init(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn write_verse(&mut self, verse: &[Line]) -> anyhow::Result<()> {
+        // Non-breaking spaces stay as characters, they are the same as
+        // `&nbsp;` in the page.
+        let verse = typography::apply_verse(self.rules, verse);
+        self.songs.last_mut().unwrap().push(verse);
+        Ok(())
+    }
+
+    fn song_space(&mut self) -> anyhow::Result<()> {
+        self.songs.push(vec![]);
+        Ok(())
+    }
+
+    fn verse_space(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn begin_song(
+        &mut self,
+        _name: &str,
+        language: &str,
+    ) -> anyhow::Result<()> {
+        self.rules = typography::rules_for(language);
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> anyhow::Result<()> {
+        let songs = serde_json::to_string(&mem::take(&mut self.songs))?;
+        // The songs must not end the script.
+        let songs = songs.replace("</", "<\\/");
+        self.writer
+            .write_all(page(&songs, &self.style).as_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -1,17 +1,56 @@
 use std::io::Write;
 
-use crate::out_fmt::OutFmt;
+use crate::{
+    Line,
+    out_fmt::OutFmt,
+    typography::{self, LangRules, NBSP},
+};
 
 pub struct Latex<W: Write> {
     writer: W,
+    rules: Option<&'static LangRules>,
 }
 
 impl<W: Write> Latex<W> {
     pub fn new(writer: W) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            rules: None,
+        }
     }
 }
 
+/// Apply the typography rules, escape the lines and write non-breaking
+/// spaces as `~`.
+pub(super) fn latex_lines(
+    rules: Option<&LangRules>,
+    verse: &[Line],
+) -> Vec<String> {
+    typography::apply_verse(rules, verse)
+        .into_iter()
+        .map(|l| escape(&l).replace(NBSP, "~"))
+        .collect()
+}
+
+/// Escape characters with special meaning in LaTeX, so that the text is
+/// printed as it is.
+pub(super) fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => res += "\\textbackslash{}",
+            '~' => res += "\\textasciitilde{}",
+            '^' => res += "\\textasciicircum{}",
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                res.push('\\');
+                res.push(c);
+            }
+            c => res.push(c),
+        }
+    }
+    res
+}
+
 impl<W: Write> OutFmt for Latex<W> {
     fn init(&mut self) -> anyhow::Result<()> {
         writeln!(
@@ -35,14 +74,14 @@ impl<W: Write> OutFmt for Latex<W> {
         Ok(())
     }
 
-    fn write_verse(&mut self, verse: &[String]) -> anyhow::Result<()> {
+    fn write_verse(&mut self, verse: &[Line]) -> anyhow::Result<()> {
         writeln!(
             self.writer,
             "\\begin{{frame}}
 \\begin{{center}}
 \\textbf{{"
         )?;
-        for l in verse {
+        for l in latex_lines(self.rules, verse) {
             writeln!(self.writer, "    {l} \\\\")?;
         }
         writeln!(
@@ -70,6 +109,23 @@ impl<W: Write> OutFmt for Latex<W> {
         Ok(())
     }
 
+    fn begin_song(
+        &mut self,
+        _name: &str,
+        language: &str,
+    ) -> anyhow::Result<()> {
+        self.rules = typography::rules_for(language);
+        Ok(())
+    }
+
+    /// Notes are beamer notes of the previous slide. They are hidden unless
+    /// the document enables them with `\setbeameroption{show notes}`.
+    fn write_notes(&mut self, notes: &[String]) -> anyhow::Result<()> {
+        let notes: Vec<_> = notes.iter().map(|n| escape(n)).collect();
+        writeln!(self.writer, "\\note{{{}}}\n", notes.join(" \\\\ "))?;
+        Ok(())
+    }
+
     fn finalize(&mut self) -> anyhow::Result<()> {
         writeln!(
             self.writer,
@@ -78,6 +134,26 @@ impl<W: Write> OutFmt for Latex<W> {
 
 \\end{{document}}"
         )?;
+        self.writer.flush()?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typography::rules_for;
+
+    #[test]
+    fn nbsp_is_tilde() {
+        let verse = ["jdu k vodám ~ 50%".into()];
+        assert_eq!(
+            latex_lines(rules_for("cs"), &verse),
+            ["jdu k~vodám \\textasciitilde{} 50\\%"]
+        );
+        assert_eq!(
+            latex_lines(None, &verse),
+            latex_lines(rules_for("en"), &verse)
+        );
+    }
+}
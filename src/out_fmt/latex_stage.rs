@@ -0,0 +1,195 @@
+use std::{io::Write, mem};
+
+use crate::{
+    Line,
+    out_fmt::{
+        OutFmt,
+        latex::{escape, latex_lines},
+    },
+    typography::{self, LangRules},
+};
+
+/// Slide of the audience presentation. Verse is [`None`] for empty slide.
+struct StageSlide {
+    song: String,
+    verse: Option<(String, Vec<String>)>,
+    notes: Vec<String>,
+}
+
+/// Stage display for the singers. There is one slide for each slide of
+/// [`crate::out_fmt::Latex`], so both presentations can be switched
+/// together. Each slide shows the current verse with its identifier and
+/// the next verse.
+pub struct LatexStage<W: Write> {
+    writer: W,
+    song: String,
+    rules: Option<&'static LangRules>,
+    /// Slide that is written when the next slide is known.
+    pending: Option<StageSlide>,
+}
+
+impl<W: Write> LatexStage<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            song: String::new(),
+            rules: None,
+            pending: None,
+        }
+    }
+
+    fn push(
+        &mut self,
+        verse: Option<(String, Vec<String>)>,
+    ) -> anyhow::Result<()> {
+        let slide = StageSlide {
+            song: self.song.clone(),
+            verse,
+            notes: vec![],
+        };
+        if let Some(prev) = self.pending.take() {
+            self.write_slide(&prev, Some(&slide))?;
+        }
+        self.pending = Some(slide);
+        Ok(())
+    }
+
+    fn write_slide(
+        &mut self,
+        cur: &StageSlide,
+        next: Option<&StageSlide>,
+    ) -> anyhow::Result<()> {
+        writeln!(
+            self.writer,
+            "\\begin{{frame}}[t]
+{{\\footnotesize\\textcolor{{gray}}{{{}}}}}
+",
+            escape(&cur.song)
+        )?;
+        match &cur.verse {
+            Some((label, lines)) => {
+                writeln!(
+                    self.writer,
+                    "\\textcolor{{orange}}{{\\textbf{{{}}}}} \\\\
+\\textbf{{",
+                    escape(label)
+                )?;
+                for l in lines {
+                    writeln!(self.writer, "    {l} \\\\")?;
+                }
+                writeln!(self.writer, "}}")?;
+            }
+            None => writeln!(self.writer, "\\textcolor{{gray}}{{(empty)}}")?,
+        }
+        for n in &cur.notes {
+            writeln!(
+                self.writer,
+                "\\\\{{\\footnotesize\\textcolor{{cyan}}{{{}}}}}",
+                escape(n)
+            )?;
+        }
+
+        writeln!(
+            self.writer,
+            "
+\\vfill
+{{\\footnotesize\\color{{gray}}"
+        )?;
+        match next.and_then(|n| n.verse.as_ref().map(|v| (n, v))) {
+            Some((n, (label, lines))) => {
+                if n.song != cur.song {
+                    writeln!(self.writer, "    {} \\\\", escape(&n.song))?;
+                }
+                writeln!(
+                    self.writer,
+                    "    Next: \\textbf{{{}}} \\\\",
+                    escape(label)
+                )?;
+                for l in lines {
+                    writeln!(self.writer, "    {l} \\\\")?;
+                }
+            }
+            None if next.is_some() => {
+                writeln!(self.writer, "    Next: (empty) \\\\")?
+            }
+            None => writeln!(self.writer, "    End. \\\\")?,
+        }
+        writeln!(
+            self.writer,
+            "}}
+\\end{{frame}}
+"
+        )?;
+        Ok(())
+    }
+}
+
+impl<W: Write> OutFmt for LatexStage<W> {
+    fn init(&mut self) -> anyhow::Result<()> {
+        writeln!(
+            self.writer,
+            "\\documentclass[17pt,aspectratio=169]{{beamer}}
+\\usepackage{{helvet}}
+\\usepackage[czech]{{babel}}
+
+\\setbeamercolor{{background canvas}}{{bg=black}}
+\\setbeamercolor{{normal text}}{{fg=white}}
+\\setbeamertemplate{{navigation symbols}}{{}}
+\\setbeamertemplate{{footline}}{{\\textcolor{{darkgray}}\
+    {{\\insertframenumber}}}}
+
+\\begin{{document}}
+"
+        )?;
+        self.push(None)
+    }
+
+    fn write_verse(&mut self, verse: &[Line]) -> anyhow::Result<()> {
+        self.write_labeled_verse("", verse)
+    }
+
+    fn song_space(&mut self) -> anyhow::Result<()> {
+        self.push(None)
+    }
+
+    fn verse_space(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Song notes are shown on the empty slide before the song.
+    fn write_notes(&mut self, notes: &[String]) -> anyhow::Result<()> {
+        if let Some(s) = &mut self.pending {
+            s.notes.extend_from_slice(notes);
+        }
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> anyhow::Result<()> {
+        self.push(None)?;
+        if let Some(last) = mem::take(&mut self.pending) {
+            self.write_slide(&last, None)?;
+        }
+        writeln!(self.writer, "\\end{{document}}")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn begin_song(
+        &mut self,
+        name: &str,
+        language: &str,
+    ) -> anyhow::Result<()> {
+        self.song = name.to_string();
+        self.rules = typography::rules_for(language);
+        Ok(())
+    }
+
+    fn write_labeled_verse(
+        &mut self,
+        label: &str,
+        verse: &[Line],
+    ) -> anyhow::Result<()> {
+        let lines = latex_lines(self.rules, verse);
+        self.push(Some((label.to_string(), lines)))
+    }
+}
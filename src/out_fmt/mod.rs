@@ -1,15 +1,83 @@
 use anyhow::Result;
 
+use crate::Line;
+
 mod fmt_type;
+mod html_overlay;
 mod latex;
+mod latex_stage;
 mod text;
 
-pub use self::{fmt_type::*, latex::*, text::*};
+pub use self::{
+    fmt_type::*, html_overlay::*, latex::*, latex_stage::*, text::*,
+};
 
+/// Output format that songs can be rendered to. See [`crate::render`].
 pub trait OutFmt {
     fn init(&mut self) -> Result<()>;
-    fn write_verse(&mut self, verse: &[String]) -> Result<()>;
+    fn write_verse(&mut self, verse: &[Line]) -> Result<()>;
     fn song_space(&mut self) -> Result<()>;
     fn verse_space(&mut self) -> Result<()>;
     fn finalize(&mut self) -> Result<()>;
+
+    /// Called before the first verse of each song. `language` is the
+    /// identifier of the song language, it selects the typography rules.
+    fn begin_song(&mut self, _name: &str, _language: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Write verse together with its identifier. Formats that don't show
+    /// the identifiers just write the verse.
+    fn write_labeled_verse(
+        &mut self,
+        _label: &str,
+        verse: &[Line],
+    ) -> Result<()> {
+        self.write_verse(verse)
+    }
+
+    /// Write notes for the operator. Notes written before the first verse
+    /// of song are for the whole song, other notes are for the last
+    /// written verse. Formats for the audience ignore them.
+    fn write_notes(&mut self, _notes: &[String]) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: OutFmt + ?Sized> OutFmt for Box<T> {
+    fn init(&mut self) -> Result<()> {
+        (**self).init()
+    }
+
+    fn write_verse(&mut self, verse: &[Line]) -> Result<()> {
+        (**self).write_verse(verse)
+    }
+
+    fn song_space(&mut self) -> Result<()> {
+        (**self).song_space()
+    }
+
+    fn verse_space(&mut self) -> Result<()> {
+        (**self).verse_space()
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        (**self).finalize()
+    }
+
+    fn begin_song(&mut self, name: &str, language: &str) -> Result<()> {
+        (**self).begin_song(name, language)
+    }
+
+    fn write_labeled_verse(
+        &mut self,
+        label: &str,
+        verse: &[Line],
+    ) -> Result<()> {
+        (**self).write_labeled_verse(label, verse)
+    }
+
+    fn write_notes(&mut self, notes: &[String]) -> Result<()> {
+        (**self).write_notes(notes)
+    }
 }
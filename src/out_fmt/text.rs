@@ -1,14 +1,25 @@
 use std::io::Write;
 
-use crate::out_fmt::OutFmt;
+use crate::{Line, out_fmt::OutFmt};
 
 pub struct Text<W: Write> {
     writer: W,
+    notes: bool,
 }
 
 impl<W: Write> Text<W> {
     pub fn new(writer: W) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            notes: false,
+        }
+    }
+
+    /// Also write the notes for the operator. Each note is on its own line
+    /// starting with `Note:`.
+    pub fn with_notes(mut self, notes: bool) -> Self {
+        self.notes = notes;
+        self
     }
 }
 
@@ -17,7 +28,7 @@ impl<W: Write> OutFmt for Text<W> {
         Ok(())
     }
 
-    fn write_verse(&mut self, verse: &[String]) -> anyhow::Result<()> {
+    fn write_verse(&mut self, verse: &[Line]) -> anyhow::Result<()> {
         for l in verse {
             writeln!(self.writer, "{l}")?;
         }
@@ -34,7 +45,17 @@ impl<W: Write> OutFmt for Text<W> {
         Ok(())
     }
 
+    fn write_notes(&mut self, notes: &[String]) -> anyhow::Result<()> {
+        if self.notes {
+            for n in notes {
+                writeln!(self.writer, "Note: {n}")?;
+            }
+        }
+        Ok(())
+    }
+
     fn finalize(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
         Ok(())
     }
 }
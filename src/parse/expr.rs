@@ -1,12 +1,13 @@
 use anyhow::{Result, bail};
 
-use crate::parse::{
-    IdentId, ident_table::IdentTable, value_table::ValueTable,
+use crate::{
+    data::Line,
+    parse::{IdentId, ident_table::IdentTable, value_table::ValueTable},
 };
 
 #[derive(Debug)]
 pub enum Expr {
-    Value(Vec<String>),
+    Value(Vec<Line>),
     Ident(IdentId),
     Add(Vec<Expr>),
 }
@@ -16,15 +17,16 @@ impl Expr {
         &self,
         vt: &ValueTable<Expr>,
         idt: &IdentTable,
-    ) -> Result<Vec<String>> {
-        let mut stack = vec![(self, false)];
+    ) -> Result<Vec<Line>> {
+        // `None` marks the end of the evaluation of identifier.
+        let mut stack = vec![Some(self)];
         let mut pred = vec![];
         let mut res = vec![];
 
-        while let Some((expr, pop_pred)) = stack.pop() {
-            match &expr {
-                Expr::Value(v) => res.extend(v.iter().cloned()),
-                Expr::Ident(i) => {
+        while let Some(expr) = stack.pop() {
+            match expr {
+                Some(Expr::Value(v)) => res.extend(v.iter().cloned()),
+                Some(Expr::Ident(i)) => {
                     if pred.contains(i) {
                         bail!(
                             "Cycle detected: `{}` depends on itself.",
@@ -32,17 +34,15 @@ impl Expr {
                         );
                     }
                     pred.push(*i);
-                    stack.push((vt.try_get(*i, idt)?, true));
+                    stack.push(None);
+                    stack.push(Some(vt.try_get(*i, idt)?));
                 }
-                Expr::Add(av) if !av.is_empty() => {
-                    stack.push((&av[av.len() - 1], true));
-                    stack.extend(av.iter().rev().skip(1).map(|a| (a, false)));
+                Some(Expr::Add(av)) => {
+                    stack.extend(av.iter().rev().map(Some));
+                }
+                None => {
+                    pred.pop();
                 }
-                _ => {}
-            }
-
-            if pop_pred {
-                pred.pop();
             }
         }
 
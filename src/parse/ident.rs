@@ -1,13 +1,29 @@
+use std::sync::Arc;
+
 use crate::parse::IdentId;
 
+/// Identifier with its name.
 #[derive(Debug, Clone)]
 pub struct Ident {
     pub id: IdentId,
-    pub name: String,
+    /// The name is shared with identifiers of the same name in other
+    /// songs.
+    pub name: Arc<str>,
 }
 
 impl Ident {
-    pub(super) fn new(id: IdentId, name: String) -> Self {
+    pub(super) fn new(id: IdentId, name: Arc<str>) -> Self {
         Self { id, name }
     }
 }
+
+/// Check whether identifier may start with the character. Identifiers are
+/// made of Unicode XID characters, `_` and `-`.
+pub fn is_ident_start(c: char) -> bool {
+    unicode_ident::is_xid_continue(c)
+}
+
+/// Check whether the character may be inside identifier.
+pub fn is_ident_continue(c: char) -> bool {
+    unicode_ident::is_xid_continue(c) || c == '-'
+}
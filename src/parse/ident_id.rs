@@ -1,9 +1,35 @@
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+};
+
+/// Identifier of verse or language in the [`IdentTable`](crate::IdentTable)
+/// of the song. Ids are ordered by the first appearance of the
+/// identifiers.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct IdentId(pub(super) usize);
 
-impl IdentId {
-    pub(super) fn next(&mut self) -> Self {
-        self.0 += 1;
-        *self
+/// Map with identifiers as keys. Identifiers are small numbers, so
+/// multiplication is enough to hash them and it is much faster than the
+/// default hasher.
+pub(crate) type IdMap<V> = HashMap<IdentId, V, BuildHasherDefault<IdHasher>>;
+
+/// Hasher for [`IdMap`].
+#[derive(Default)]
+pub(crate) struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*b);
+        }
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.0 = (i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
     }
 }
@@ -1,59 +1,44 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
-use crate::parse::{IdentId, ident::Ident};
+use crate::parse::{IdentId, ident::Ident, interner::intern};
 
-#[derive(Debug)]
+/// Identifiers of single song. Ids are given in order of the first
+/// appearance and the names are interned.
+#[derive(Debug, Clone)]
 pub struct IdentTable {
-    idents: HashMap<IdentId, Ident>,
-    id: IdentId,
-    tables: Vec<HashMap<String, Ident>>,
+    idents: Vec<Ident>,
+    ids: HashMap<Arc<str>, IdentId>,
 }
 
 impl IdentTable {
     pub fn new() -> Self {
         Self {
-            idents: HashMap::new(),
-            id: IdentId(0),
-            tables: vec![HashMap::new()],
+            idents: vec![],
+            ids: HashMap::new(),
         }
     }
 
+    /// Get id of the name. New names get new id.
     pub fn get_id(&mut self, n: &str) -> IdentId {
-        assert!(!self.tables.is_empty());
-
-        for t in self.tables.iter().rev() {
-            if let Some(i) = t.get(n) {
-                return i.id;
-            }
+        if let Some(id) = self.ids.get(n) {
+            return *id;
         }
 
-        let id = self.id.next();
-        let ident = Ident::new(id, n.to_string());
-
-        self.tables
-            .last_mut()
-            .unwrap()
-            .insert(n.to_string(), ident.clone());
-        self.idents.insert(id, ident);
+        let id = IdentId(self.idents.len() + 1);
+        let name = intern(n);
+        self.ids.insert(name.clone(), id);
+        self.idents.push(Ident::new(id, name));
 
         id
     }
 
+    /// Get the identifier. Returns [`None`] if it is not in the table.
     pub fn get_ident(&self, id: IdentId) -> Option<&Ident> {
-        self.idents.get(&id)
-    }
-
-    pub fn new_scope(&mut self) {
-        self.tables.push(HashMap::new());
-    }
-
-    pub fn pop_scope(&mut self) {
-        self.tables.pop();
-        if self.tables.is_empty() {
-            self.new_scope();
-        }
+        self.idents.get(id.0.checked_sub(1)?)
     }
 
+    /// Get the name of the identifier. Identifiers that are not in the
+    /// table are named by their id, e.g. `<3>`.
     pub fn get_name(&self, id: IdentId) -> Cow<'_, str> {
         if let Some(id) = self.get_ident(id) {
             Cow::Borrowed(&id.name)
@@ -62,3 +47,9 @@ impl IdentTable {
         }
     }
 }
+
+impl Default for IdentTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
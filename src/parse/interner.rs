@@ -0,0 +1,33 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+/// Names of identifiers shared by all the parsed songs, so that each name is
+/// stored only once. Names that are no longer used by any song are removed
+/// when the set grows.
+static NAMES: LazyLock<Mutex<Names>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct Names {
+    set: HashSet<Arc<str>>,
+    /// Size of the set at which unused names are removed.
+    limit: usize,
+}
+
+/// Get the shared instance of the name.
+pub fn intern(name: &str) -> Arc<str> {
+    // The set is always valid, so poisoned lock can be used.
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(n) = names.set.get(name) {
+        return n.clone();
+    }
+
+    if names.set.len() >= names.limit {
+        names.set.retain(|n| Arc::strong_count(n) > 1);
+        names.limit = (names.set.len() * 2).max(1024);
+    }
+    let n: Arc<str> = name.into();
+    names.set.insert(n.clone());
+    n
+}
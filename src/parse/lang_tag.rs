@@ -0,0 +1,136 @@
+use anyhow::{Result, bail};
+
+/// Validate language tag according to BCP 47 and normalize the case of its
+/// subtags, e.g. `PT-br` becomes `pt-BR` and `zh-hans` becomes `zh-Hans`.
+/// Only the syntax is validated, the subtags are not checked against the
+/// registry.
+pub fn normalize_lang_tag(tag: &str) -> Result<String> {
+    if let Some(c) = tag
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-')
+    {
+        bail!(
+            "Invalid character `{c}` in language tag `{tag}`. Language tags \
+            may contain only ASCII letters, digits and `-`."
+        );
+    }
+    let subtags: Vec<_> = tag.split('-').collect();
+    if subtags.iter().any(|s| s.is_empty()) {
+        bail!("Language tag `{tag}` has empty subtag.");
+    }
+    if let Some(s) = subtags.iter().find(|s| s.len() > 8) {
+        bail!(
+            "Subtag `{s}` in language tag `{tag}` is longer than 8 \
+            characters."
+        );
+    }
+
+    let alpha = |s: &str| s.bytes().all(|b| b.is_ascii_alphabetic());
+    let digit = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let lower = |s: &str| s.to_ascii_lowercase();
+
+    let mut it = subtags.into_iter().peekable();
+    let first = it.next().unwrap_or_default();
+    let mut res = vec![lower(first)];
+    if first.eq_ignore_ascii_case("x") {
+        if it.peek().is_none() {
+            bail!("Private use language tag `{tag}` has no subtags.");
+        }
+        res.extend(it.map(lower));
+        return Ok(res.join("-"));
+    }
+    if first.len() < 2 || !alpha(first) {
+        bail!(
+            "Language tag `{tag}` must start with language subtag of 2 to 8 \
+            letters, e.g. `en` or `pt-BR`."
+        );
+    }
+
+    // 0: extended language, 1: script, 2: region, 3: variants,
+    // 4: extensions
+    let mut stage = 0;
+    let mut extlangs = 0;
+    while let Some(s) = it.next() {
+        let len = s.len();
+        if stage == 0
+            && first.len() <= 3
+            && extlangs < 3
+            && len == 3
+            && alpha(s)
+        {
+            extlangs += 1;
+            res.push(lower(s));
+        } else if stage < 1 && len == 4 && alpha(s) {
+            stage = 1;
+            res.push(s[..1].to_ascii_uppercase() + &lower(&s[1..]));
+        } else if stage < 2 && (len == 2 && alpha(s) || len == 3 && digit(s)) {
+            stage = 2;
+            res.push(s.to_ascii_uppercase());
+        } else if stage <= 3
+            && (len >= 5 || len == 4 && s.as_bytes()[0].is_ascii_digit())
+        {
+            stage = 3;
+            res.push(lower(s));
+        } else if len == 1 {
+            res.push(lower(s));
+            let private = s.eq_ignore_ascii_case("x");
+            let mut cnt = 0;
+            while let Some(e) = it.next_if(|e| private || e.len() >= 2) {
+                res.push(lower(e));
+                cnt += 1;
+            }
+            if cnt == 0 {
+                bail!(
+                    "Extension `{s}` in language tag `{tag}` has no subtags."
+                );
+            }
+            stage = 4;
+        } else {
+            bail!("Unexpected subtag `{s}` in language tag `{tag}`.");
+        }
+    }
+
+    Ok(res.join("-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_case() {
+        for (tag, norm) in [
+            ("cs", "cs"),
+            ("EN", "en"),
+            ("PT-br", "pt-BR"),
+            ("zh-hans", "zh-Hans"),
+            ("zh-CMN-hans-cn", "zh-cmn-Hans-CN"),
+            ("es-419", "es-419"),
+            ("sl-ROZAJ-biske", "sl-rozaj-biske"),
+            ("de-CH-1901", "de-CH-1901"),
+            ("en-a-BBB-x-A-Priv", "en-a-bbb-x-a-priv"),
+            ("X-Whatever", "x-whatever"),
+        ] {
+            assert_eq!(normalize_lang_tag(tag).unwrap(), norm, "{tag}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        for tag in [
+            "",
+            "c",
+            "čeština",
+            "en_US",
+            "en--US",
+            "en-",
+            "1en",
+            "toolongtag",
+            "en-a",
+            "x",
+            "en-US-CZ",
+        ] {
+            assert!(normalize_lang_tag(tag).is_err(), "{tag}");
+        }
+    }
+}
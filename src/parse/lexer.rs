@@ -1,96 +1,145 @@
-use std::mem;
+use std::{borrow::Cow, mem};
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, bail};
 
-use crate::parse::{IdentId, ident_table::IdentTable, token::Token};
+use crate::{
+    data::Line,
+    parse::{
+        IdentId, ident_table::IdentTable, is_ident_continue, is_ident_start,
+        token::Token,
+    },
+    syntax::Pos,
+};
 
+/// Lexer over the whole source. Tokens are ranges in the source, so the
+/// values are copied only if they contain escapes.
 #[derive(Debug)]
-pub struct Lexer<I: Iterator<Item = Result<char>>> {
-    it: I,
-    cur: Option<char>,
+pub struct Lexer<'a> {
+    src: &'a str,
+    /// Offset of the next character.
+    pos: usize,
     pub idents: IdentTable,
     id: IdentId,
-    buf: String,
-    lines: Option<Vec<String>>,
+    string: Cow<'a, str>,
+    lines: Option<Vec<Line>>,
+    /// Line of `pos` and offset of the start of the line.
+    line: (usize, usize),
+    /// Offset of the start of the last token.
+    tok_start: usize,
+    /// Line of the start of the last token and offset of its start.
+    tok_line: (usize, usize),
 }
 
-impl<I: Iterator<Item = Result<char>>> Lexer<I> {
-    pub fn new(mut it: I) -> Result<Self> {
-        let cur = it.next().transpose()?;
-        Ok(Self {
-            it,
-            cur,
+impl<'a> Lexer<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            pos: 0,
             idents: IdentTable::new(),
             id: IdentId(0),
-            buf: String::new(),
+            string: Cow::Borrowed(""),
             lines: None,
-        })
+            line: (1, 0),
+            tok_start: 0,
+            tok_line: (1, 0),
+        }
     }
 
     pub fn next(&mut self) -> Result<Token> {
-        while matches!(self.cur, Some(c) if c.is_ascii_whitespace()) {
-            self.next_chr()?;
-        }
+        self.skip_whitespace()?;
+        self.tok_start = self.pos;
+        self.tok_line = self.line;
 
-        match self.cur {
-            Some(c) if c.is_ascii_alphanumeric() => self.next_alnum(),
+        match self.cur() {
+            Some(c) if is_ident_start(c) => self.next_alnum(),
             Some('"') => self.next_string(),
             Some('#') => self.next_declare(),
             None => Ok(Token::Eof),
-            _ => self.next_op_punct(),
+            Some(c) => self.next_op_punct(c),
         }
     }
 
     pub fn last_string(&self) -> &str {
-        &self.buf
+        &self.string
     }
 
     pub fn last_id(&self) -> IdentId {
         self.id
     }
 
-    pub fn last_line_string(&mut self) -> Vec<String> {
+    /// Position of the start of the last token.
+    pub fn last_pos(&self) -> Pos {
+        let (line, start) = self.tok_line;
+        let col = self.src[start..self.tok_start].chars().count() + 1;
+        Pos { line, col }
+    }
+
+    pub fn last_line_string(&mut self) -> Vec<Line> {
         assert!(self.lines.is_some());
         mem::take(&mut self.lines).unwrap()
     }
 
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.rest().as_bytes().first() {
+                // ASCII characters that are [`is_space`].
+                Some(b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c) => {
+                    self.advance(1)
+                }
+                Some(b'/') => {
+                    self.tok_start = self.pos;
+                    self.tok_line = self.line;
+                    self.advance(1);
+                    if self.cur() != Some('/') {
+                        bail!("Unknown operator or punctuation `/`.");
+                    }
+                    let len =
+                        self.rest().find('\n').unwrap_or(self.rest().len());
+                    self.advance(len);
+                }
+                Some(b) if b.is_ascii() => return Ok(()),
+                Some(_) => match self.cur() {
+                    Some(c) if is_space(c) => self.advance(c.len_utf8()),
+                    _ => return Ok(()),
+                },
+                None => return Ok(()),
+            }
+        }
+    }
+
     fn next_alnum(&mut self) -> Result<Token> {
-        self.buf.clear();
-        self.read_alnum()?;
+        let word = self.read_alnum();
 
-        match self.buf.as_str() {
+        match word {
             "name" => return Ok(Token::KwName),
             "order" => return Ok(Token::KwOrder),
+            "note" => return Ok(Token::KwNote),
             _ => {}
         }
 
-        if self.cur != Some('{') {
-            self.id = self.idents.get_id(&self.buf);
+        if self.cur() != Some('{') {
+            self.id = self.idents.get_id(word);
             return Ok(Token::Ident);
         }
 
-        match self.buf.as_str() {
+        match word {
             "l" => self.next_line_string(),
             s => bail!("Unknonw special string type `{s}`."),
         }
     }
 
     fn next_declare(&mut self) -> Result<Token> {
-        self.next_chr()?; // #
-        self.buf.clear();
-        self.read_alnum()?;
+        self.advance(1); // #
 
-        match self.buf.as_str() {
+        match self.read_alnum() {
             "language" => Ok(Token::DecLanguage),
             s => bail!("Unknown declaration `{s}`"),
         }
     }
 
-    fn next_op_punct(&mut self) -> Result<Token> {
-        assert!(self.cur.is_some());
-
-        let c = self.cur.unwrap();
-        self.next_chr()?;
+    fn next_op_punct(&mut self, c: char) -> Result<Token> {
+        self.advance(c.len_utf8());
 
         let tok = match c {
             ':' => Token::Colon,
@@ -108,86 +157,205 @@ impl<I: Iterator<Item = Result<char>>> Lexer<I> {
     }
 
     fn next_string(&mut self) -> Result<Token> {
-        self.buf.clear();
+        self.advance(1); // "
+        let rest = self.rest();
+        let Some(end) = rest.find(['"', '\\']) else {
+            return self.unclosed("Missing closing '\"'.");
+        };
+        if rest.as_bytes()[end] == b'"' {
+            self.string = Cow::Borrowed(&rest[..end]);
+            self.advance(end + 1);
+            return Ok(Token::String);
+        }
 
+        let mut res = String::with_capacity(end * 2);
+        let mut chars = rest.char_indices();
         loop {
-            self.next_chr()?;
-            match self.cur {
-                Some('"') => break,
-                Some('\\') => {
-                    self.next_chr()?;
-                    self.buf
-                        .push(self.cur.ok_or_else(|| {
-                            anyhow!("Missing closing '\"'.")
-                        })?);
+            match chars.next() {
+                Some((i, '"')) => {
+                    self.advance(i + 1);
+                    break;
                 }
-                Some(c) => self.buf.push(c),
-                None => bail!("Missing closing '\"'."),
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, c)) => res.push(c),
+                    None => return self.unclosed("Missing closing '\"'."),
+                },
+                Some((_, c)) => res.push(c),
+                None => return self.unclosed("Missing closing '\"'."),
             }
         }
-
-        self.next_chr()?; // "
+        self.string = Cow::Owned(res);
         Ok(Token::String)
     }
 
     fn next_line_string(&mut self) -> Result<Token> {
+        self.advance(1); // {
         let mut lines = vec![];
-        let mut line = String::new();
-
         loop {
-            self.next_chr()?;
-            if self.cur == Some('\n') {
-                if !line.is_empty() {
-                    lines.push(mem::take(&mut line));
+            let (line, end) = self.read_line()?;
+            lines.extend(line);
+            if end {
+                break;
+            }
+        }
+        self.lines = Some(lines);
+        Ok(Token::LineString)
+    }
+
+    /// Read single line of line string. Whitespace is normalized. Returns
+    /// the line if it is not empty and whether it is the last line.
+    fn read_line(&mut self) -> Result<(Option<Line>, bool)> {
+        let rest = self.rest();
+        let Some(end) =
+            rest.bytes().position(|b| matches!(b, b'\n' | b'}' | b'\\'))
+        else {
+            return self.unclosed("Missing closing '}'.");
+        };
+        if rest.as_bytes()[end] != b'\\' {
+            let line = normalize_line(&rest[..end]);
+            self.advance(end + 1);
+            return Ok((line, rest.as_bytes()[end] == b'}'));
+        }
+
+        // The line has escapes, so it must be built.
+        let mut line = String::with_capacity(end * 2);
+        let mut space = None;
+        let mut chars = rest.char_indices();
+        let last = loop {
+            let Some((i, c)) = chars.next() else {
+                return self.unclosed("Missing closing '}'.");
+            };
+            match c {
+                '\n' | '}' => {
+                    self.advance(i + 1);
+                    break c == '}';
+                }
+                c if is_space(c) => {
+                    space = Some(join_space(space, c));
+                    continue;
                 }
-                continue;
+                _ => {}
             }
-            let space = matches!(self.cur, Some(c) if c.is_ascii_whitespace());
-            while matches!(self.cur, Some(c) if c.is_ascii_whitespace()) {
-                self.next_chr()?;
+
+            if let Some(s) = space.take()
+                && !line.is_empty()
+            {
+                line.push(s);
             }
-            if !line.is_empty() && space {
-                line.push(' ');
+            if c == '\\' {
+                let Some((_, c)) = chars.next() else {
+                    return self.unclosed("Missing closing '}'.");
+                };
+                line.push(c);
+            } else {
+                line.push(c);
             }
+        };
+        Ok(((!line.is_empty()).then(|| line.into()), last))
+    }
 
-            match self.cur {
-                Some('}') => break,
-                Some('\\') => {
-                    self.next_chr()?;
-                    line.push(
-                        self.cur
-                            .ok_or_else(|| anyhow!("Missing closing '}}'."))?,
-                    );
-                }
-                Some(c) => line.push(c),
-                None => bail!("Missing closing '}}'."),
-            }
-        }
+    fn read_alnum(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !is_ident_continue(c))
+            .unwrap_or(rest.len());
+        self.advance(len);
+        &rest[..len]
+    }
 
-        self.next_chr()?; // }
+    /// Report missing end of token. The rest of the input is skipped so that
+    /// the lexer cannot get stuck.
+    fn unclosed<T>(&mut self, msg: &str) -> Result<T> {
+        self.advance(self.rest().len());
+        bail!("{msg}")
+    }
 
-        if !line.is_empty() {
-            lines.push(line);
-        }
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
 
-        self.lines = Some(lines);
+    fn cur(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
 
-        Ok(Token::LineString)
+    /// Move by `len` bytes and keep track of the lines.
+    fn advance(&mut self, len: usize) {
+        let skipped = &self.src[self.pos..self.pos + len];
+        if let Some(i) = skipped.rfind('\n') {
+            self.line.0 += skipped.bytes().filter(|b| *b == b'\n').count();
+            self.line.1 = self.pos + i + 1;
+        }
+        self.pos += len;
+    }
+}
+
+/// Normalize whitespace in single line without escapes.
+fn normalize_line(line: &str) -> Option<Line> {
+    let line = line.trim_matches(is_space);
+    if line.is_empty() {
+        return None;
     }
 
-    fn read_alnum(&mut self) -> Result<()> {
-        while let Some(c) = self.cur {
-            if !c.is_ascii_alphanumeric() {
-                break;
+    // Most lines have only single spaces and so they can be used as they
+    // are. Lead bytes of UTF-8 encoded whitespace are checked first, because
+    // decoding all the characters is slow.
+    let simple = !line.contains("  ")
+        && !line.bytes().enumerate().any(|(i, b)| match b {
+            b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => true,
+            0xc2 | 0xe1..=0xe3 | 0xef => {
+                line[i..].chars().next().is_some_and(is_space)
             }
-            self.buf.push(c);
-            self.next_chr()?;
+            _ => false,
+        });
+    if simple {
+        return Some(line.into());
+    }
+
+    let mut res = String::with_capacity(line.len());
+    let mut space = None;
+    for c in line.chars() {
+        if is_space(c) {
+            space = Some(join_space(space, c));
+            continue;
         }
-        Ok(())
+        res.extend(space.take());
+        res.push(c);
+    }
+    Some(res.into())
+}
+
+/// Whitespace between tokens. Byte order mark is also whitespace, so that
+/// it doesn't have to be removed from the input.
+pub(crate) fn is_space(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
+}
+
+/// Whitespace that doesn't allow line break. It is kept in `l{...}`.
+fn is_non_breaking_space(c: char) -> bool {
+    matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Add character to sequence of whitespace that is replaced by single
+/// space. The sequence is non-breaking space if any of its characters is.
+pub(crate) fn join_space(space: Option<char>, c: char) -> char {
+    if space == Some('\u{a0}') || is_non_breaking_space(c) {
+        '\u{a0}'
+    } else {
+        ' '
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_str;
 
-    fn next_chr(&mut self) -> Result<Option<char>> {
-        self.cur = self.it.next().transpose()?;
-        Ok(self.cur)
+    #[test]
+    fn ascii_whitespace() {
+        let song = parse_str(
+            "name = {cs: \"a\"}\n\x0cv1:\x0bl{ x }\r\n\
+            order\t= [v1]",
+        )
+        .unwrap();
+        assert_eq!(song.default_config().verses[0][0].as_ref(), "x");
     }
 }
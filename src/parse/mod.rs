@@ -2,39 +2,67 @@ mod expr;
 mod ident;
 mod ident_id;
 mod ident_table;
+mod interner;
+mod lang_tag;
 mod lexer;
+mod parse_error;
 mod parser;
 mod token;
 mod value_table;
 
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
-};
+use std::{io::Read, path::Path};
 
 use anyhow::Result;
 
-use utf8_chars::BufReadCharsExt;
-
 use crate::{
     data::Song,
-    parse::{lexer::Lexer, parser::Parser},
+    encoding::{InputEncoding, read_file},
+    parse::parser::Parser,
 };
 
-pub use self::ident_id::*;
+pub(crate) use self::lexer::{is_space, join_space};
+pub use self::{
+    ident::*, ident_id::*, ident_table::*, lang_tag::*, lexer::*,
+    parse_error::*, token::*,
+};
 
-pub fn parse_iterator(
-    i: impl IntoIterator<Item = Result<char>>,
-) -> Result<Song> {
-    let mut parser = Parser::new(Lexer::new(i.into_iter())?)?;
+/// Parse song from string. This is the fastest way to parse song, other
+/// sources are read into string first.
+pub fn parse_str(s: &str) -> Result<Song> {
+    let mut parser = Parser::new(Lexer::new(s))?;
     parser.parse_song()
 }
 
-pub fn parse_read(mut r: impl BufRead) -> Result<Song> {
-    parse_iterator(r.chars().map(|a| a.map_err(|e| e.into())))
+/// Parse song from UTF-8 reader.
+pub fn parse_read(mut r: impl Read) -> Result<Song> {
+    let mut s = String::new();
+    r.read_to_string(&mut s)?;
+    parse_str(&s)
 }
 
+/// Parse song from file. The encoding is detected, see
+/// [`InputEncoding::Auto`].
 pub fn parse_file(f: impl AsRef<Path>) -> Result<Song> {
-    parse_read(BufReader::new(File::open(f)?))
+    parse_str(&read_file(f, InputEncoding::Auto)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_from_errors() {
+        let src = "v1: l{ a }\n\
+            v2 = l{ b }\n\
+            v3: l{ c }\n\
+            name = ,\n\
+            order = [v1, v3]\n";
+        let errs = parse_str(src).unwrap_err().downcast::<ParseErrors>();
+        let ParseErrors(errs) = errs.unwrap();
+        let pos: Vec<_> =
+            errs.iter().map(|e| (e.pos.line, e.pos.col)).collect();
+        // Parsing continues after each error, so `v3` is defined and only
+        // the two syntax errors are reported.
+        assert_eq!(pos, [(2, 4), (4, 8)]);
+    }
 }
@@ -0,0 +1,46 @@
+use std::fmt::Display;
+
+use crate::syntax::Pos;
+
+/// Error at position in the parsed song.
+#[derive(Debug)]
+pub struct ParseError {
+    pub pos: Pos,
+    pub msg: String,
+}
+
+/// All the errors found when parsing single song.
+#[derive(Debug)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl ParseError {
+    /// Attach position to the error, unless it already has one.
+    pub fn at(pos: Pos, e: anyhow::Error) -> Self {
+        e.downcast().unwrap_or_else(|e| Self {
+            pos,
+            msg: e.to_string(),
+        })
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pos, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Display for ParseErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
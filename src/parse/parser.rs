@@ -1,111 +1,181 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem};
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 
 use crate::{
     data::{Song, SongConf},
     parse::{
-        IdentId, expr::Expr, lexer::Lexer, token::Token,
-        value_table::ValueTable,
+        IdMap, IdentId, ParseError, ParseErrors,
+        expr::Expr,
+        lang_tag::normalize_lang_tag,
+        lexer::Lexer,
+        token::Token,
+        value_table::{Definition, ValueTable},
     },
+    syntax::Pos,
 };
 
+/// Error for `note` used as verse identifier. It is keyword since notes
+/// were added, so older songs may use it.
+const NOTE_KEYWORD: &str = "`note` is keyword for notes and it cannot be \
+    used as verse identifier. Rename the verse, e.g. to `note-1`.";
+
+/// Verse identifiers in order with their notes.
+type Order = Vec<(IdentId, Option<String>)>;
+
 #[derive(Debug)]
-pub struct Parser<I: Iterator<Item = Result<char>>> {
-    lex: Lexer<I>,
+pub struct Parser<'a> {
+    lex: Lexer<'a>,
     cur: Token,
+    /// The token before `cur`. Used to find start of item when recovering
+    /// from errors.
+    prev: Token,
+    /// Set when the lexer failed and so `cur` was not updated.
+    lex_failed: bool,
     values: ValueTable<Expr>,
-    names: HashMap<IdentId, String>,
-    order: Vec<IdentId>,
+    /// Notes of the verses. Each verse has entry so that verse without note
+    /// hides note of verse from outer scope.
+    verse_notes: ValueTable<Option<String>>,
+    names: HashMap<IdentId, (String, Pos)>,
+    /// Notes for the whole song from the top level.
+    notes: Vec<String>,
+    order: Order,
+    order_pos: Option<Pos>,
+    /// Positions of the `#language` declarations.
+    languages: HashMap<IdentId, Pos>,
+    /// The first declared language.
+    default: Option<IdentId>,
+    errors: Vec<ParseError>,
 }
 
-impl<I: Iterator<Item = Result<char>>> Parser<I> {
-    pub fn new(mut lex: Lexer<I>) -> Result<Self> {
-        let cur = lex.next()?;
-        Ok(Self {
+impl<'a> Parser<'a> {
+    pub fn new(lex: Lexer<'a>) -> Result<Self> {
+        let mut res = Self {
             lex,
-            cur,
+            cur: Token::Eof,
+            prev: Token::Eof,
+            lex_failed: false,
             values: ValueTable::new(),
+            verse_notes: ValueTable::new(),
             names: HashMap::new(),
+            notes: vec![],
             order: vec![],
-        })
+            order_pos: None,
+            languages: HashMap::new(),
+            default: None,
+            errors: vec![],
+        };
+        if let Err(e) = res.next() {
+            res.recover(e);
+        }
+        Ok(res)
     }
 
+    /// Parse the whole song. The parser recovers from errors so that all of
+    /// them are found. If there are any, they are returned as
+    /// [`ParseErrors`].
     pub fn parse_song(&mut self) -> Result<Song> {
         let mut configs = HashMap::new();
-        let mut default = None;
 
         loop {
-            match self.cur {
-                Token::KwName => {
-                    let names = self.parse_names()?;
-                    self.names.extend(names);
-                }
-                Token::KwOrder => self.order = self.parse_order()?,
-                Token::DecLanguage => {
-                    let conf = self.parse_song_conf()?;
-                    if default.is_none() {
-                        default = Some(conf.language);
+            let res = match self.cur {
+                Token::KwName => self.parse_names(),
+                Token::KwOrder => self.parse_top_order(),
+                Token::KwNote => self.parse_note().map(|n| self.notes.push(n)),
+                Token::DecLanguage => self.parse_song_conf().map(|conf| {
+                    if let Some(conf) = conf {
+                        configs.entry(conf.language).or_insert(conf);
                     }
-                    configs.insert(conf.language, conf);
-                }
-                Token::Ident => self.parse_verse()?,
+                }),
+                Token::Ident => self.parse_verse(),
                 Token::Eof => break,
-                t => bail!("Unexpected token `{t:?}`."),
+                t => Err(anyhow!("Unexpected token `{t:?}`.")),
+            };
+            if let Err(e) = res {
+                self.recover(e);
             }
         }
 
-        let default = if let Some(d) = default {
-            d
-        } else {
+        if self.languages.is_empty() {
             let id = self.lex.idents.get_id("generic");
-            let conf = self.construct_config(String::new(), None, id)?;
-            configs.insert(id, conf);
-            id
-        };
+            let pos = self.order_pos.unwrap_or(Pos { line: 1, col: 1 });
+            match self.construct_config(None, None, vec![], id) {
+                Ok(conf) => {
+                    configs.insert(id, conf);
+                }
+                Err(e) => self.errors.push(ParseError::at(pos, e)),
+            }
+            self.default = Some(id);
+        }
+
+        if !self.errors.is_empty() {
+            self.errors.sort_by_key(|e| e.pos);
+            return Err(ParseErrors(mem::take(&mut self.errors)).into());
+        }
 
-        Ok(Song { default, configs })
+        Ok(Song {
+            // There is always language if there are no errors.
+            default: self.default.unwrap(),
+            configs,
+            idents: mem::take(&mut self.lex.idents),
+        })
     }
 
-    fn parse_names(&mut self) -> Result<HashMap<IdentId, String>> {
+    fn parse_names(&mut self) -> Result<()> {
         self.expect_nexts([Token::Assign, Token::OpenBracket])?;
 
-        let mut res = HashMap::new();
-
-        loop {
-            self.next()?;
-            if self.cur == Token::CloseBracket {
-                break;
-            }
-
+        self.next()?; // {
+        while self.cur != Token::CloseBracket {
             self.expect(Token::Ident)?;
-            let id = self.lex.last_id();
+            let id = self.language_tag();
+            let pos = self.lex.last_pos();
             self.expect_nexts([Token::Colon, Token::String])?;
             let name = self.lex.last_string().to_owned();
-            res.insert(id, name);
+            if let Some((_, prev)) = self.names.get(&id) {
+                let lang = self.ident_name(id);
+                let e = redefinition(
+                    &format!("Name for the language `{lang}`"),
+                    pos,
+                    *prev,
+                );
+                self.errors.push(e);
+            } else {
+                self.names.insert(id, (name, pos));
+            }
 
+            self.next()?;
             self.skip_if(Token::Comma)?;
         }
 
         self.next()?; // }
 
-        Ok(res)
+        Ok(())
     }
 
-    fn parse_order(&mut self) -> Result<Vec<IdentId>> {
+    fn parse_top_order(&mut self) -> Result<()> {
+        let pos = self.lex.last_pos();
+        let order = self.parse_order()?;
+        if let Some(prev) = self.order_pos {
+            self.errors.push(redefinition("`order`", pos, prev));
+        } else {
+            self.order = order;
+            self.order_pos = Some(pos);
+        }
+        Ok(())
+    }
+
+    fn parse_order(&mut self) -> Result<Order> {
         self.expect_nexts([Token::Assign, Token::OpenSq])?;
 
         let mut res = vec![];
 
-        loop {
-            self.next()?;
-            if self.cur == Token::CloseSq {
-                break;
-            }
-
+        self.next()?; // [
+        while self.cur != Token::CloseSq {
             self.expect(Token::Ident)?;
-            res.push(self.lex.last_id());
+            let id = self.lex.last_id();
 
+            self.next()?;
+            res.push((id, self.parse_attached_note()?));
             self.skip_if(Token::Comma)?;
         }
 
@@ -114,39 +184,91 @@ impl<I: Iterator<Item = Result<char>>> Parser<I> {
         Ok(res)
     }
 
-    fn parse_song_conf(&mut self) -> Result<SongConf> {
+    /// Parse `#language` block. Returns [`None`] if the configuration for
+    /// the language cannot be constructed.
+    fn parse_song_conf(&mut self) -> Result<Option<SongConf>> {
+        let decl_pos = self.lex.last_pos();
         self.expect_next(Token::Ident)?;
-        let language = self.lex.last_id();
+        let language = self.language_tag();
+        if let Some(prev) = self.languages.get(&language) {
+            let name = self.ident_name(language);
+            let e =
+                redefinition(&format!("Language `{name}`"), decl_pos, *prev);
+            self.errors.push(e);
+        } else {
+            self.languages.insert(language, decl_pos);
+        }
+        self.default.get_or_insert(language);
         self.next()?; // language
 
-        let mut name = None;
-        let mut order = None;
+        let mut name: Option<(String, Pos)> = None;
+        let mut order: Option<(Order, Pos)> = None;
+        let mut notes = vec![];
 
         self.new_scope();
         loop {
-            match self.cur {
-                Token::KwName => name = Some(self.parse_name()?),
-                Token::KwOrder => order = Some(self.parse_order()?),
-                Token::Ident => self.parse_verse()?,
+            let pos = self.lex.last_pos();
+            let res = match self.cur {
+                Token::KwName => self.parse_name().map(|n| match name {
+                    Some((_, prev)) => {
+                        self.errors.push(redefinition("`name`", pos, prev))
+                    }
+                    None => name = Some((n, pos)),
+                }),
+                Token::KwOrder => self.parse_order().map(|o| match order {
+                    Some((_, prev)) => {
+                        self.errors.push(redefinition("`order`", pos, prev))
+                    }
+                    None => order = Some((o, pos)),
+                }),
+                Token::KwNote => self.parse_note().map(|n| notes.push(n)),
+                Token::Ident => self.parse_verse(),
                 Token::DecLanguage => break,
                 Token::Eof => break,
-                t => bail!("Unexpected token `{t:?}`."),
+                t => Err(anyhow!("Unexpected token `{t:?}`.")),
+            };
+            if let Err(e) = res {
+                self.recover(e);
             }
         }
 
-        let res =
-            self.construct_config(name.unwrap_or_default(), order, language)?;
+        let pos = order
+            .as_ref()
+            .map(|(_, p)| *p)
+            .or(self.order_pos)
+            .unwrap_or(decl_pos);
+        let res = self.construct_config(
+            name.map(|(n, _)| n),
+            order.map(|(o, _)| o),
+            notes,
+            language,
+        );
         self.pop_scope();
 
-        Ok(res)
+        Ok(res
+            .map_err(|e| self.errors.push(ParseError::at(pos, e)))
+            .ok())
     }
 
     fn parse_verse(&mut self) -> Result<()> {
         let id = self.lex.last_id();
+        let pos = self.lex.last_pos();
         self.expect_next(Token::Colon)?;
         self.next()?;
         let expr = self.parse_expr()?;
-        self.values.set(id, expr);
+        let note = self.parse_attached_note()?;
+        // Redefinition is reported for the value.
+        _ = self.verse_notes.set(id, note, pos);
+        // Shadowing verse from the top level inside `#language` is
+        // intentional, only redefinition in the same scope is an error.
+        match self.values.set(id, expr, pos) {
+            Ok(Definition::New | Definition::Shadows(_)) => {}
+            Err(prev) => {
+                let name = self.ident_name(id);
+                let e = redefinition(&format!("Verse `{name}`"), pos, prev);
+                self.errors.push(e);
+            }
+        }
         Ok(())
     }
 
@@ -160,6 +282,7 @@ impl<I: Iterator<Item = Result<char>>> Parser<I> {
                 Token::Ident => {
                     exprs.push(Expr::Ident(self.lex.last_id()));
                 }
+                Token::KwNote => bail!("{NOTE_KEYWORD}"),
                 _ => bail!(
                     "Expected line string or identifier but found `{:?}`.",
                     self.cur
@@ -173,6 +296,29 @@ impl<I: Iterator<Item = Result<char>>> Parser<I> {
         Ok(Expr::Add(exprs))
     }
 
+    /// Parse `note = "note"`.
+    fn parse_note(&mut self) -> Result<String> {
+        if self.next()? == Token::Colon {
+            bail!("{NOTE_KEYWORD}");
+        }
+        self.expect(Token::Assign)?;
+        self.expect_next(Token::String)?;
+        let note = self.lex.last_string().to_owned();
+        self.next()?;
+        Ok(note)
+    }
+
+    /// Parse `note "note"` after verse or order entry if it is present.
+    fn parse_attached_note(&mut self) -> Result<Option<String>> {
+        if self.cur != Token::KwNote {
+            return Ok(None);
+        }
+        self.expect_next(Token::String)?;
+        let note = self.lex.last_string().to_owned();
+        self.next()?;
+        Ok(Some(note))
+    }
+
     fn parse_name(&mut self) -> Result<String> {
         self.expect_nexts([Token::Assign, Token::String])?;
         let name = self.lex.last_string().to_owned();
@@ -182,33 +328,73 @@ impl<I: Iterator<Item = Result<char>>> Parser<I> {
 
     fn construct_config(
         &self,
-        name: String,
-        order: Option<Vec<IdentId>>,
+        name: Option<String>,
+        order: Option<Order>,
+        notes: Vec<String>,
         language: IdentId,
     ) -> Result<SongConf> {
+        let order = order.unwrap_or_else(|| self.order.clone());
         let mut verses = vec![];
-        for id in order.as_ref().unwrap_or(&self.order) {
-            let Some(v) = self.values.get(*id) else {
-                let name = &self.lex.idents.get_ident(*id).unwrap().name;
-                bail!("Missing verse text for the identifier `{name}`.");
+        let mut verse_notes = vec![];
+        // Verses repeat in the order, so each is evaluated only once.
+        let mut evaluated = IdMap::default();
+        for (id, note) in &order {
+            let verse = match evaluated.get(id) {
+                Some(v) => Vec::clone(v),
+                None => {
+                    let Some(v) = self.values.get(*id) else {
+                        let name = self.ident_name(*id);
+                        bail!(
+                            "Missing verse text for the identifier `{name}`."
+                        );
+                    };
+                    let v = v.eval(&self.values, &self.lex.idents)?;
+                    evaluated.insert(*id, v.clone());
+                    v
+                }
             };
-            verses.push(v.eval(&self.values, &self.lex.idents)?);
+            verses.push(verse);
+            let def = self.verse_notes.get(*id).cloned().flatten();
+            verse_notes.push(def.into_iter().chain(note.clone()).collect());
         }
+        let name = name
+            .or_else(|| self.names.get(&language).map(|(n, _)| n.clone()))
+            .unwrap_or_default();
         Ok(SongConf {
             language,
-            _name: name,
+            name,
+            order: order.into_iter().map(|(id, _)| id).collect(),
             verses,
+            notes: self.notes.iter().cloned().chain(notes).collect(),
+            verse_notes,
         })
     }
 
+    /// Get identifier of the normalized language tag in the last token.
+    /// Invalid tag is reported and used as is.
+    fn language_tag(&mut self) -> IdentId {
+        let id = self.lex.last_id();
+        match normalize_lang_tag(self.ident_name(id)) {
+            Ok(tag) => self.lex.idents.get_id(&tag),
+            Err(e) => {
+                self.errors.push(ParseError::at(self.lex.last_pos(), e));
+                id
+            }
+        }
+    }
+
+    fn ident_name(&self, id: IdentId) -> &str {
+        &self.lex.idents.get_ident(id).unwrap().name
+    }
+
     fn new_scope(&mut self) {
         self.values.new_scope();
-        self.lex.idents.new_scope();
+        self.verse_notes.new_scope();
     }
 
     fn pop_scope(&mut self) {
         self.values.pop_scope();
-        self.lex.idents.pop_scope();
+        self.verse_notes.pop_scope();
     }
 
     fn skip_if(&mut self, i: Token) -> Result<()> {
@@ -234,14 +420,82 @@ impl<I: Iterator<Item = Result<char>>> Parser<I> {
     }
 
     fn expect(&mut self, t: Token) -> Result<()> {
+        if t == Token::Ident && self.cur == Token::KwNote {
+            bail!("{NOTE_KEYWORD}");
+        }
         if self.cur != t {
             bail!("Expected the token `{t:?}` but found `{:?}`", self.cur);
         }
         Ok(())
     }
 
+    /// Record the error and skip to the start of the next item.
+    fn recover(&mut self, e: anyhow::Error) {
+        self.errors.push(ParseError::at(self.lex.last_pos(), e));
+
+        // If lexer failed, `cur` is the token before the failed one, so it
+        // must be skipped.
+        let mut skip = mem::take(&mut self.lex_failed);
+        loop {
+            let at_item = match self.cur {
+                Token::KwName
+                | Token::KwOrder
+                | Token::DecLanguage
+                | Token::Eof => true,
+                // Note is start of item unless it is attached to verse or
+                // order entry or it is misused as identifier.
+                Token::KwNote => !matches!(
+                    self.prev,
+                    Token::Ident
+                        | Token::LineString
+                        | Token::Add
+                        | Token::Colon
+                        | Token::Comma
+                        | Token::OpenSq
+                ),
+                // Identifier is start of verse unless it is part of
+                // expression or list.
+                Token::Ident => !matches!(
+                    self.prev,
+                    Token::Add
+                        | Token::Colon
+                        | Token::Comma
+                        | Token::OpenSq
+                        | Token::OpenBracket
+                ),
+                _ => false,
+            };
+            if at_item && !skip {
+                return;
+            }
+            skip = false;
+            if let Err(e) = self.next() {
+                self.errors.push(ParseError::at(self.lex.last_pos(), e));
+                skip = mem::take(&mut self.lex_failed);
+            }
+        }
+    }
+
     fn next(&mut self) -> Result<Token> {
-        self.cur = self.lex.next()?;
-        Ok(self.cur)
+        match self.lex.next() {
+            Ok(t) => {
+                self.prev = mem::replace(&mut self.cur, t);
+                Ok(t)
+            }
+            Err(e) => {
+                self.lex_failed = true;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn redefinition(what: &str, pos: Pos, prev: Pos) -> ParseError {
+    ParseError {
+        pos,
+        msg: format!(
+            "{what} is already defined in this scope. Previous definition \
+            is at {prev}."
+        ),
     }
 }
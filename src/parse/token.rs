@@ -1,3 +1,4 @@
+/// Kind of token in wohu source.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Token {
     Ident,
@@ -17,6 +18,7 @@ pub enum Token {
 
     KwName,  // name
     KwOrder, // order
+    KwNote,  // note
 
     DecLanguage, // #language
 
@@ -1,33 +1,59 @@
-use std::collections::HashMap;
-
 use anyhow::{Result, anyhow};
 
-use crate::parse::{IdentId, ident_table::IdentTable};
+use crate::{
+    parse::{IdMap, IdentId, ident_table::IdentTable},
+    syntax::Pos,
+};
+
+/// Result of successful definition of value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Definition {
+    /// There is no other value with the same identifier.
+    New,
+    /// The value shadows value defined in outer scope at the given position.
+    Shadows(Pos),
+}
 
 #[derive(Debug)]
 pub struct ValueTable<T> {
-    tables: Vec<HashMap<IdentId, T>>,
+    tables: Vec<IdMap<(T, Pos)>>,
 }
 
 impl<T> ValueTable<T> {
     pub fn new() -> Self {
         Self {
-            tables: vec![HashMap::new()],
+            tables: vec![IdMap::default()],
         }
     }
 
-    pub fn set(&mut self, id: IdentId, value: T) {
+    /// Define value in the current scope. If the value is already defined in
+    /// the current scope, it is not changed and position of the previous
+    /// definition is returned as error.
+    pub fn set(
+        &mut self,
+        id: IdentId,
+        value: T,
+        pos: Pos,
+    ) -> Result<Definition, Pos> {
         assert!(!self.tables.is_empty());
-        self.tables.last_mut().unwrap().insert(id, value);
+
+        let outer = self.get_pos(id);
+        let table = self.tables.last_mut().unwrap();
+        if let Some((_, prev)) = table.get(&id) {
+            return Err(*prev);
+        }
+        table.insert(id, (value, pos));
+
+        Ok(outer.map_or(Definition::New, Definition::Shadows))
     }
 
     pub fn get(&self, id: IdentId) -> Option<&T> {
-        for t in self.tables.iter().rev() {
-            if let Some(v) = t.get(&id) {
-                return Some(v);
-            }
-        }
-        None
+        self.get_entry(id).map(|(v, _)| v)
+    }
+
+    /// Get position of the visible definition.
+    pub fn get_pos(&self, id: IdentId) -> Option<Pos> {
+        self.get_entry(id).map(|(_, p)| *p)
     }
 
     pub fn try_get(&self, id: IdentId, idt: &IdentTable) -> Result<&T> {
@@ -37,7 +63,7 @@ impl<T> ValueTable<T> {
     }
 
     pub fn new_scope(&mut self) {
-        self.tables.push(HashMap::new());
+        self.tables.push(IdMap::default());
     }
 
     pub fn pop_scope(&mut self) {
@@ -46,6 +72,15 @@ impl<T> ValueTable<T> {
             self.new_scope();
         }
     }
+
+    fn get_entry(&self, id: IdentId) -> Option<&(T, Pos)> {
+        for t in self.tables.iter().rev() {
+            if let Some(v) = t.get(&id) {
+                return Some(v);
+            }
+        }
+        None
+    }
 }
 
 impl<T> Default for ValueTable<T> {
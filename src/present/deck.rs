@@ -0,0 +1,127 @@
+use wohu::{Song, typography};
+
+/// Single slide shown on the display.
+#[derive(Debug, Clone)]
+pub struct Slide {
+    /// Identifier of the verse.
+    pub label: String,
+    pub lines: Vec<String>,
+    /// Notes for the operator. Never shown to the audience.
+    pub notes: Vec<String>,
+}
+
+/// Song in single language.
+#[derive(Debug, Clone)]
+pub struct DeckConf {
+    pub language: String,
+    pub name: String,
+    /// Notes for the operator about the whole song.
+    pub notes: Vec<String>,
+    pub slides: Vec<Slide>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeckSong {
+    pub path: String,
+    /// Index of the default configuration.
+    pub default: usize,
+    pub confs: Vec<DeckConf>,
+}
+
+/// All the songs prepared for presentation.
+#[derive(Debug, Clone, Default)]
+pub struct Deck {
+    pub songs: Vec<DeckSong>,
+}
+
+impl Deck {
+    pub fn new<'a>(
+        songs: impl IntoIterator<Item = (&'a str, &'a Song)>,
+    ) -> Self {
+        let songs = songs
+            .into_iter()
+            .map(|(path, s)| {
+                let confs: Vec<_> = s
+                    .ordered_configs()
+                    .into_iter()
+                    .map(|c| {
+                        let language =
+                            s.idents.get_name(c.language).into_owned();
+                        let rules = typography::rules_for(&language);
+                        DeckConf {
+                            name: c.name.clone(),
+                            notes: c.notes.clone(),
+                            slides: c
+                                .order
+                                .iter()
+                                .zip(&c.verses)
+                                .zip(&c.verse_notes)
+                                .map(|((id, lines), notes)| Slide {
+                                    label: s.idents.get_name(*id).into_owned(),
+                                    lines: typography::apply_verse(
+                                        rules, lines,
+                                    ),
+                                    notes: notes.clone(),
+                                })
+                                .collect(),
+                            language,
+                        }
+                    })
+                    .collect();
+                let default = s.idents.get_name(s.default);
+                DeckSong {
+                    path: path.to_string(),
+                    default: confs
+                        .iter()
+                        .position(|c| c.language == default)
+                        .unwrap_or_default(),
+                    confs,
+                }
+            })
+            .collect();
+        Self { songs }
+    }
+
+    /// Split the slides so that each has at most the given number of lines.
+    /// The parts keep the label of the verse, notes are kept only with the
+    /// first part.
+    pub fn split_slides(&mut self, max_lines: usize) {
+        let max_lines = max_lines.max(1);
+        for c in self.songs.iter_mut().flat_map(|s| &mut s.confs) {
+            c.slides = c
+                .slides
+                .iter()
+                .flat_map(|s| {
+                    s.lines.chunks(max_lines).enumerate().map(|(i, l)| Slide {
+                        label: s.label.clone(),
+                        lines: l.to_vec(),
+                        notes: if i == 0 { s.notes.clone() } else { vec![] },
+                    })
+                })
+                .collect();
+        }
+    }
+
+    /// All the languages used by the songs.
+    pub fn languages(&self) -> Vec<&str> {
+        let mut res: Vec<_> = self
+            .songs
+            .iter()
+            .flat_map(|s| &s.confs)
+            .map(|c| c.language.as_str())
+            .collect();
+        res.sort();
+        res.dedup();
+        res
+    }
+}
+
+impl DeckSong {
+    /// Get configuration for the language. If the song doesn't have the
+    /// language, the default configuration is used.
+    pub fn conf(&self, language: Option<&str>) -> &DeckConf {
+        language
+            .and_then(|l| self.confs.iter().find(|c| c.language == l))
+            .unwrap_or(&self.confs[self.default])
+    }
+}
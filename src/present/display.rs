@@ -0,0 +1,34 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::{cmd::PendingFile, present::Presenter};
+
+/// Output that shows the slides to the audience.
+pub trait Display {
+    /// Show the current state of the presentation.
+    fn update(&mut self, p: &Presenter) -> Result<()>;
+}
+
+/// Writes the shown slide to a file as plain text. The file is empty when
+/// the display is blank. It is replaced atomically, so it can be followed
+/// by `wohu display` or by any other program.
+pub struct FileDisplay {
+    path: PathBuf,
+}
+
+impl FileDisplay {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Display for FileDisplay {
+    fn update(&mut self, p: &Presenter) -> Result<()> {
+        let (pending, mut file) = PendingFile::create(self.path.clone(), 0)?;
+        for l in p.shown().map(|s| s.lines.as_slice()).unwrap_or_default() {
+            writeln!(file, "{l}")?;
+        }
+        pending.commit()
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::{Mutex, MutexGuard};
+
+use serde_json::{Value, json};
+
+use crate::present::{Action, Presenter, Slide};
+
+/// Presentation shared by all the controllers and displays. Every change
+/// increases the version, so that the others know that they should update.
+pub struct Live {
+    state: Mutex<(Presenter, u64)>,
+}
+
+impl Live {
+    pub fn new(p: Presenter) -> Self {
+        Self {
+            state: Mutex::new((p, 0)),
+        }
+    }
+
+    /// Apply the action. Returns `false` if the action cannot be done.
+    pub fn apply(&self, action: Action) -> bool {
+        let mut state = self.lock();
+        let ok = state.0.apply(action);
+        if ok {
+            state.1 += 1;
+        }
+        ok
+    }
+
+    /// Access the current state of the presentation.
+    pub fn with<R>(&self, f: impl FnOnce(&Presenter) -> R) -> R {
+        f(&self.lock().0)
+    }
+
+    pub fn version(&self) -> u64 {
+        self.lock().1
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (Presenter, u64)> {
+        // Presenter is always valid, so poisoned lock can be used.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// State of the presentation as JSON sent to the web pages. The notes for
+/// the operator are included only if `notes` is set, so that the pages for
+/// the audience never receive them.
+pub fn state_json(p: &Presenter, notes: bool) -> Value {
+    let slide = |s: Option<&Slide>| {
+        s.map(|s| {
+            let mut res = json!({ "label": s.label, "lines": s.lines });
+            if notes {
+                res["notes"] = json!(s.notes);
+            }
+            res
+        })
+    };
+    let conf = p.conf();
+    let mut res = json!({
+        "song": p.song(),
+        "songs": (0..p.deck().songs.len())
+            .map(|s| p.conf_of(s).map(|c| c.name.as_str()))
+            .collect::<Vec<_>>(),
+        "path": p.deck().songs.get(p.song()).map(|s| &s.path),
+        "name": conf.map(|c| &c.name),
+        "language": conf.map(|c| &c.language),
+        "languages": p.deck().languages(),
+        "slide": p.slide(),
+        "labels": conf
+            .map(|c| c.slides.iter().map(|s| &s.label).collect::<Vec<_>>()),
+        "blank": p.is_blank(),
+        "current": slide(p.current()),
+        "upcoming": slide(p.upcoming()),
+        "shown": slide(p.shown()),
+    });
+    if notes {
+        res["notes"] = json!(conf.map(|c| &c.notes));
+    }
+    res
+}
+
+/// Parse action sent by a web page, e.g. `{"action":"verse","verse":"c"}`.
+pub fn parse_action(v: &Value) -> Option<Action> {
+    let idx = |k: &str| v[k].as_u64().map(|i| i as usize);
+    let text = |k: &str| v[k].as_str().map(str::to_string);
+    Some(match v["action"].as_str()? {
+        "next" => Action::Next,
+        "prev" => Action::Prev,
+        "next-song" => Action::NextSong,
+        "prev-song" => Action::PrevSong,
+        "song" => Action::Song(idx("song")?),
+        "slide" => Action::Slide(idx("slide")?),
+        "verse" => Action::Verse(text("verse")?),
+        "blank" => Action::ToggleBlank,
+        "language" => Action::Language(text("language")?),
+        "next-language" => Action::NextLanguage,
+        _ => return None,
+    })
+}
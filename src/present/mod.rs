@@ -0,0 +1,13 @@
+mod deck;
+mod display;
+mod live;
+mod osc;
+mod presenter;
+mod server;
+mod term;
+mod tui;
+
+pub use self::{
+    deck::*, display::*, live::*, osc::*, presenter::*, server::*, term::*,
+    tui::*,
+};
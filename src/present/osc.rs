@@ -0,0 +1,353 @@
+use std::{
+    net::{SocketAddr, UdpSocket},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::present::{Action, Live, Presenter};
+
+/// How long the server waits for messages before checking for changes of
+/// the presentation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Prefix of all the addresses.
+const PREFIX: &str = "/wohu";
+
+/// Argument of OSC message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub addr: String,
+    pub args: Vec<OscArg>,
+}
+
+/// Listens for OSC messages over UDP and applies them to the presentation.
+/// Everybody who sent a message, and the feedback address, receives
+/// messages with the current song and verse after each change.
+pub struct OscServer {
+    socket: UdpSocket,
+    live: Arc<Live>,
+    peers: Vec<SocketAddr>,
+}
+
+impl OscServer {
+    pub fn bind(
+        addr: &str,
+        feedback: Option<&str>,
+        live: Arc<Live>,
+    ) -> Result<Self> {
+        let socket = UdpSocket::bind(addr)
+            .with_context(|| format!("Failed to listen on `{addr}`."))?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        let mut peers = vec![];
+        if let Some(f) = feedback {
+            let f = f
+                .parse()
+                .with_context(|| format!("Invalid feedback address `{f}`."))?;
+            peers.push(f);
+        }
+        Ok(Self {
+            socket,
+            live,
+            peers,
+        })
+    }
+
+    /// Run the server in background thread.
+    pub fn spawn(mut self) {
+        thread::spawn(move || self.run());
+    }
+
+    fn run(&mut self) {
+        let mut buf = [0; 65536];
+        let mut version = self.live.version();
+        loop {
+            // Errors are timeouts or caused by peers that are no longer
+            // listening.
+            if let Ok((len, peer)) = self.socket.recv_from(&mut buf) {
+                if !self.peers.contains(&peer) {
+                    self.peers.push(peer);
+                }
+                // Invalid messages are ignored, there is nobody to report
+                // them to.
+                for m in decode(&buf[..len]).unwrap_or_default() {
+                    if let Some(a) = osc_action(&m) {
+                        self.live.apply(a);
+                    }
+                }
+            }
+
+            let cur = self.live.version();
+            if cur != version {
+                version = cur;
+                self.feedback();
+            }
+        }
+    }
+
+    fn feedback(&self) {
+        let msgs = self.live.with(feedback_messages);
+        for m in &msgs {
+            let data = encode(m);
+            for p in &self.peers {
+                _ = self.socket.send_to(&data, p);
+            }
+        }
+    }
+}
+
+/// Convert message to action. Songs and slides are numbered from 1.
+fn osc_action(m: &OscMessage) -> Option<Action> {
+    let cmd = m.addr.strip_prefix(PREFIX)?;
+    let int = || match m.args.first()? {
+        OscArg::Int(i) => usize::try_from(*i).ok(),
+        OscArg::Float(f) if *f >= 0. => Some(*f as usize),
+        OscArg::Str(s) => s.parse().ok(),
+        OscArg::Bool(_) | OscArg::Float(_) => None,
+    };
+    let text = || match m.args.first()? {
+        OscArg::Str(s) => Some(s.clone()),
+        _ => None,
+    };
+    Some(match cmd {
+        "/next" => Action::Next,
+        "/prev" => Action::Prev,
+        "/song/next" => Action::NextSong,
+        "/song/prev" => Action::PrevSong,
+        "/song" => Action::Song(int()?.checked_sub(1)?),
+        "/slide" => Action::Slide(int()?.checked_sub(1)?),
+        "/verse" => Action::Verse(text()?),
+        "/blank" => match m.args.first() {
+            None => Action::ToggleBlank,
+            Some(OscArg::Bool(b)) => Action::Blank(*b),
+            Some(_) => Action::Blank(int()? != 0),
+        },
+        "/language/next" => Action::NextLanguage,
+        "/language" => Action::Language(text()?),
+        _ => return None,
+    })
+}
+
+/// Messages describing the current state of the presentation.
+fn feedback_messages(p: &Presenter) -> Vec<OscMessage> {
+    let msg = |addr: &str, args| OscMessage {
+        addr: format!("{PREFIX}{addr}"),
+        args,
+    };
+    let conf = p.conf();
+    vec![
+        msg(
+            "/song",
+            vec![
+                OscArg::Int(p.song() as i32 + 1),
+                OscArg::Str(conf.map(|c| c.name.clone()).unwrap_or_default()),
+            ],
+        ),
+        msg(
+            "/verse",
+            vec![
+                OscArg::Str(
+                    p.current().map(|s| s.label.clone()).unwrap_or_default(),
+                ),
+                OscArg::Int(p.slide() as i32 + 1),
+            ],
+        ),
+        msg("/blank", vec![OscArg::Int(p.is_blank() as i32)]),
+        msg(
+            "/language",
+            vec![OscArg::Str(
+                conf.map(|c| c.language.clone()).unwrap_or_default(),
+            )],
+        ),
+    ]
+}
+
+/// Decode OSC packet. Messages in bundles are returned in order, time tags
+/// are ignored.
+pub fn decode(data: &[u8]) -> Result<Vec<OscMessage>> {
+    let mut r = Reader { data };
+    if data.starts_with(b"#bundle\0") {
+        r.string()?;
+        r.take(8)?; // time tag
+        let mut res = vec![];
+        while !r.data.is_empty() {
+            let len = r.int()? as usize;
+            res.extend(decode(r.take(len)?)?);
+        }
+        return Ok(res);
+    }
+
+    let addr = r.string()?;
+    if !addr.starts_with('/') {
+        bail!("Invalid OSC address `{addr}`.");
+    }
+    let tags = if r.data.is_empty() {
+        String::new()
+    } else {
+        r.string()?
+    };
+    let mut args = vec![];
+    for t in tags.strip_prefix(',').unwrap_or_default().chars() {
+        args.push(match t {
+            'i' => OscArg::Int(r.int()?),
+            'f' => OscArg::Float(f32::from_bits(r.int()? as u32)),
+            's' => OscArg::Str(r.string()?),
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            t => bail!("Unsupported OSC type tag `{t}`."),
+        });
+    }
+    Ok(vec![OscMessage { addr, args }])
+}
+
+/// Encode OSC message.
+pub fn encode(m: &OscMessage) -> Vec<u8> {
+    let mut res = vec![];
+    write_string(&mut res, &m.addr);
+    let mut tags = ",".to_string();
+    for a in &m.args {
+        tags.push(match a {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::Str(_) => 's',
+            OscArg::Bool(true) => 'T',
+            OscArg::Bool(false) => 'F',
+        });
+    }
+    write_string(&mut res, &tags);
+    for a in &m.args {
+        match a {
+            OscArg::Int(i) => res.extend(i.to_be_bytes()),
+            OscArg::Float(f) => res.extend(f.to_be_bytes()),
+            OscArg::Str(s) => write_string(&mut res, s),
+            OscArg::Bool(_) => {}
+        }
+    }
+    res
+}
+
+/// Write null terminated string padded to multiple of 4 bytes.
+fn write_string(res: &mut Vec<u8>, s: &str) {
+    res.extend(s.as_bytes());
+    res.resize((res.len() + 4) & !3, 0);
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.data.len() {
+            bail!("Unexpected end of OSC packet.");
+        }
+        let (res, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(res)
+    }
+
+    fn int(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let Some(len) = self.data.iter().position(|b| *b == 0) else {
+            bail!("Unterminated OSC string.");
+        };
+        let s = String::from_utf8(self.take(len)?.to_vec())?;
+        self.take((4 - len % 4).min(self.data.len()))?;
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(addr: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage {
+            addr: addr.to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let msgs = [
+            msg("/wohu/next", vec![]),
+            // Address and string that fill whole multiple of 4 bytes still
+            // need the terminating null.
+            msg("/abc", vec![OscArg::Str("four".into())]),
+            msg(
+                "/wohu/song",
+                vec![
+                    OscArg::Int(-7),
+                    OscArg::Float(1.5),
+                    OscArg::Str("Píseň".into()),
+                    OscArg::Bool(true),
+                    OscArg::Bool(false),
+                    OscArg::Str(String::new()),
+                ],
+            ),
+        ];
+        for m in msgs {
+            let data = encode(&m);
+            assert_eq!(data.len() % 4, 0);
+            assert_eq!(decode(&data).unwrap(), vec![m]);
+        }
+    }
+
+    #[test]
+    fn bundle() {
+        let a = msg("/wohu/next", vec![]);
+        let b = msg("/wohu/slide", vec![OscArg::Int(3)]);
+        let mut data = b"#bundle\0".to_vec();
+        data.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        for m in [&a, &b] {
+            let m = encode(m);
+            data.extend((m.len() as i32).to_be_bytes());
+            data.extend(m);
+        }
+        assert_eq!(decode(&data).unwrap(), vec![a, b]);
+    }
+
+    #[test]
+    fn invalid() {
+        let data = encode(&msg("/wohu/song", vec![OscArg::Int(1)]));
+        assert!(decode(&data[..data.len() - 2]).is_err());
+        assert!(decode(b"wohu\0\0\0\0").is_err());
+        assert!(decode(b"/a\0\0,x\0\0").is_err());
+        assert!(decode(b"/abc").is_err());
+    }
+
+    #[test]
+    fn actions() {
+        let action = |addr, args| osc_action(&msg(addr, args));
+        assert_eq!(action("/wohu/next", vec![]), Some(Action::Next));
+        assert_eq!(
+            action("/wohu/song", vec![OscArg::Int(2)]),
+            Some(Action::Song(1))
+        );
+        assert_eq!(
+            action("/wohu/slide", vec![OscArg::Str("1".into())]),
+            Some(Action::Slide(0))
+        );
+        assert_eq!(action("/wohu/song", vec![OscArg::Int(0)]), None);
+        assert_eq!(
+            action("/wohu/blank", vec![OscArg::Float(0.)]),
+            Some(Action::Blank(false))
+        );
+        assert_eq!(action("/wohu/blank", vec![]), Some(Action::ToggleBlank));
+        assert_eq!(action("/other/next", vec![]), None);
+    }
+}
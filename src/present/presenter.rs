@@ -0,0 +1,200 @@
+use crate::present::{Deck, DeckConf, Slide};
+
+/// Change of the presentation requested by the operator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    Prev,
+    NextSong,
+    PrevSong,
+    /// Go to the first slide of the song with the given index.
+    Song(usize),
+    /// Go to the slide with the given index in the current song.
+    Slide(usize),
+    /// Go to the next slide with the verse identifier.
+    Verse(String),
+    /// Blank or unblank the displays.
+    Blank(bool),
+    ToggleBlank,
+    Language(String),
+    NextLanguage,
+}
+
+/// State of live presentation.
+#[derive(Debug, Clone)]
+pub struct Presenter {
+    deck: Deck,
+    song: usize,
+    slide: usize,
+    blank: bool,
+    language: Option<String>,
+}
+
+impl Presenter {
+    pub fn new(deck: Deck) -> Self {
+        Self {
+            deck,
+            song: 0,
+            slide: 0,
+            blank: false,
+            language: None,
+        }
+    }
+
+    /// Apply the action. Returns `false` if the action cannot be done.
+    pub fn apply(&mut self, action: Action) -> bool {
+        match action {
+            Action::Next => self.next(),
+            Action::Prev => self.prev(),
+            Action::NextSong => self.go_to_song(self.song + 1),
+            Action::PrevSong => {
+                self.song != 0 && self.go_to_song(self.song - 1)
+            }
+            Action::Song(s) => self.go_to_song(s),
+            Action::Slide(s) => {
+                let ok = self.conf().is_some_and(|c| s < c.slides.len());
+                if ok {
+                    self.slide = s;
+                }
+                ok
+            }
+            Action::Verse(v) => self.go_to_verse(&v),
+            Action::Blank(b) => {
+                self.blank = b;
+                true
+            }
+            Action::ToggleBlank => {
+                self.blank = !self.blank;
+                true
+            }
+            Action::Language(l) => self.set_language(l),
+            Action::NextLanguage => {
+                let langs = self.deck.languages();
+                let cur = self.language.as_deref();
+                let idx = langs.iter().position(|l| Some(*l) == cur);
+                match idx.map_or(langs.first(), |i| langs.get(i + 1)) {
+                    Some(l) => self.set_language(l.to_string()),
+                    None => {
+                        self.language = None;
+                        self.clamp_slide();
+                        true
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    /// Index of the current song.
+    pub fn song(&self) -> usize {
+        self.song
+    }
+
+    /// Index of the current slide within the current song.
+    pub fn slide(&self) -> usize {
+        self.slide
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.blank
+    }
+
+    /// The current song in the selected language.
+    pub fn conf(&self) -> Option<&DeckConf> {
+        self.conf_of(self.song)
+    }
+
+    /// The current slide, even if the display is blank.
+    pub fn current(&self) -> Option<&Slide> {
+        self.conf()?.slides.get(self.slide)
+    }
+
+    /// Slide that follows the current slide, possibly from the next song.
+    pub fn upcoming(&self) -> Option<&Slide> {
+        let conf = self.conf()?;
+        conf.slides.get(self.slide + 1).or_else(|| {
+            ((self.song + 1)..self.deck.songs.len())
+                .find_map(|s| self.conf_of(s)?.slides.first())
+        })
+    }
+
+    /// Slide that should be visible on the displays.
+    pub fn shown(&self) -> Option<&Slide> {
+        if self.blank { None } else { self.current() }
+    }
+
+    /// The song with the index in the selected language.
+    pub fn conf_of(&self, song: usize) -> Option<&DeckConf> {
+        Some(self.deck.songs.get(song)?.conf(self.language.as_deref()))
+    }
+
+    fn next(&mut self) -> bool {
+        let Some(conf) = self.conf() else {
+            return false;
+        };
+        if self.slide + 1 < conf.slides.len() {
+            self.slide += 1;
+            return true;
+        }
+        self.go_to_song(self.song + 1)
+    }
+
+    fn prev(&mut self) -> bool {
+        if self.slide != 0 {
+            self.slide -= 1;
+            return true;
+        }
+        if self.song == 0 {
+            return false;
+        }
+        self.song -= 1;
+        self.slide =
+            self.conf().map_or(0, |c| c.slides.len().saturating_sub(1));
+        true
+    }
+
+    fn go_to_song(&mut self, song: usize) -> bool {
+        if song >= self.deck.songs.len() {
+            return false;
+        }
+        self.song = song;
+        self.slide = 0;
+        true
+    }
+
+    /// Find the verse after the current slide, wrapping around to the start
+    /// of the song.
+    fn go_to_verse(&mut self, label: &str) -> bool {
+        let Some(conf) = self.conf() else {
+            return false;
+        };
+        let len = conf.slides.len();
+        let found = (1..=len)
+            .map(|i| (self.slide + i) % len)
+            .find(|i| conf.slides[*i].label == label);
+        if let Some(i) = found {
+            self.slide = i;
+        }
+        found.is_some()
+    }
+
+    /// Language tags are compared without case.
+    fn set_language(&mut self, lang: String) -> bool {
+        let langs = self.deck.languages();
+        let Some(lang) = langs.iter().find(|l| l.eq_ignore_ascii_case(&lang))
+        else {
+            return false;
+        };
+        self.language = Some(lang.to_string());
+        self.clamp_slide();
+        true
+    }
+
+    fn clamp_slide(&mut self) {
+        let len = self.conf().map_or(0, |c| c.slides.len());
+        self.slide = self.slide.min(len.saturating_sub(1));
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    io::{self, BufRead, BufReader, ErrorKind, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use tungstenite::{
+    Message, WebSocket, handshake::derive_accept_key, protocol::Role,
+};
+use wohu::out_fmt::live_overlay_page;
+
+use crate::present::{Live, parse_action, state_json};
+
+const DISPLAY_PAGE: &str = include_str!("web/display.html");
+const CONTROL_PAGE: &str = include_str!("web/control.html");
+const STAGE_PAGE: &str = include_str!("web/stage.html");
+
+/// How long the connection waits for messages before checking for changes
+/// of the presentation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// HTTP server with the display, stage and control pages. The pages receive
+/// the state of the presentation and send actions through WebSocket at
+/// `/ws`.
+/// Pages for the operator use `/ws/operator`, which also sends the notes.
+pub struct WebServer {
+    listener: TcpListener,
+    live: Arc<Live>,
+    /// The overlay page with its style.
+    overlay: Arc<str>,
+}
+
+impl WebServer {
+    pub fn bind(addr: &str, live: Arc<Live>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .with_context(|| format!("Failed to listen on `{addr}`."))?;
+        Ok(Self {
+            listener,
+            live,
+            overlay: live_overlay_page(&[]).into(),
+        })
+    }
+
+    /// Set the default style of the overlay page, see
+    /// [`wohu::out_fmt::HtmlOverlay::with_style`].
+    pub fn with_overlay_style(mut self, style: &[(String, String)]) -> Self {
+        self.overlay = live_overlay_page(style).into();
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accept connections forever. Each connection has its own thread.
+    pub fn run(self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let live = self.live.clone();
+            let overlay = self.overlay.clone();
+            thread::spawn(move || {
+                // The client may disconnect at any time, there is nobody to
+                // report the error to.
+                _ = handle(stream, &live, &overlay);
+            });
+        }
+        Ok(())
+    }
+
+    /// Run the server in background thread.
+    pub fn spawn(self) {
+        thread::spawn(move || self.run());
+    }
+}
+
+fn handle(stream: TcpStream, live: &Live, overlay: &str) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        bail!("Invalid request.");
+    };
+    let path = path.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut ws_key = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':')
+            && k.trim().eq_ignore_ascii_case("sec-websocket-key")
+        {
+            ws_key = Some(v.trim().to_string());
+        }
+    }
+
+    let mut stream = reader.into_inner();
+    if method != "GET" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "",
+        );
+    }
+    match (path.as_str(), ws_key) {
+        ("/ws", Some(key)) => websocket(stream, &key, live, false),
+        ("/ws/operator", Some(key)) => websocket(stream, &key, live, true),
+        ("/" | "/display", _) => {
+            respond(&mut stream, "200 OK", "text/html", DISPLAY_PAGE)
+        }
+        ("/control", _) => {
+            respond(&mut stream, "200 OK", "text/html", CONTROL_PAGE)
+        }
+        ("/overlay", _) => {
+            respond(&mut stream, "200 OK", "text/html", overlay)
+        }
+        ("/stage", _) => {
+            respond(&mut stream, "200 OK", "text/html", STAGE_PAGE)
+        }
+        ("/state", _) => {
+            let state = live.with(|p| state_json(p, false)).to_string();
+            respond(&mut stream, "200 OK", "application/json", &state)
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found."),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {content_type}; charset=utf-8\r\n\
+        Content-Length: {}\r\n\
+        Cache-Control: no-store\r\n\
+        Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Send the state whenever it changes and apply the received actions. The
+/// state includes the notes only if `notes` is set.
+fn websocket(
+    mut stream: TcpStream,
+    key: &str,
+    live: &Live,
+    notes: bool,
+) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+        Upgrade: websocket\r\n\
+        Connection: Upgrade\r\n\
+        Sec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    )?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut ws = WebSocket::from_raw_socket(stream, Role::Server, None);
+
+    let mut version = None;
+    loop {
+        let cur = live.version();
+        if version != Some(cur) {
+            version = Some(cur);
+            let state = live.with(|p| state_json(p, notes)).to_string();
+            ws.send(Message::text(state))?;
+        }
+
+        match ws.read() {
+            Ok(Message::Text(t)) => {
+                let action = serde_json::from_str(&t)
+                    .ok()
+                    .and_then(|v| parse_action(&v));
+                if let Some(a) = action {
+                    live.apply(a);
+                }
+            }
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if is_timeout(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
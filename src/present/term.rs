@@ -0,0 +1,109 @@
+use std::{
+    io::{Write, stdout},
+    time::Duration,
+};
+
+use anyhow::Result;
+use termal::{
+    codes,
+    raw::{
+        Terminal, disable_raw_mode, enable_raw_mode,
+        events::{Event, Key, KeyCode, Modifiers},
+        term_size,
+    },
+};
+
+/// Fullscreen terminal in raw mode. The original state of the terminal is
+/// restored when this is dropped.
+pub struct RawTerm {
+    term: Terminal,
+}
+
+impl RawTerm {
+    pub fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        let mut res = Self {
+            term: Terminal::stdio(),
+        };
+        res.draw(&format!(
+            "{}{}",
+            codes::ENABLE_ALTERNATIVE_BUFFER,
+            codes::HIDE_CURSOR
+        ))?;
+        Ok(res)
+    }
+
+    /// Wait for key press at most for the given time.
+    pub fn read_key(&mut self, timeout: Duration) -> Result<Option<Key>> {
+        match self.term.read_timeout(timeout)? {
+            Some(Event::KeyPress(k)) => Ok(Some(k)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Width and height of the terminal. Some terminals don't report their
+    /// size, so the default size is used instead.
+    pub fn size(&self) -> (usize, usize) {
+        match term_size() {
+            Ok(s) if s.char_width != 0 && s.char_height != 0 => {
+                (s.char_width, s.char_height)
+            }
+            _ => (80, 24),
+        }
+    }
+
+    /// Clear the screen and draw the lines.
+    pub fn draw_lines(
+        &mut self,
+        lines: impl IntoIterator<Item = String>,
+    ) -> Result<()> {
+        let mut s = codes::CLEAR.to_string();
+        for (i, l) in lines.into_iter().enumerate() {
+            if i != 0 {
+                s += "\r\n";
+            }
+            s += &l;
+        }
+        self.draw(&s)
+    }
+
+    fn draw(&mut self, s: &str) -> Result<()> {
+        let mut out = stdout().lock();
+        out.write_all(s.as_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for RawTerm {
+    fn drop(&mut self) {
+        _ = self.draw(&format!(
+            "{}{}{}",
+            codes::SHOW_CURSOR,
+            codes::DISABLE_ALTERNATIVE_BUFFER,
+            codes::RESET
+        ));
+        _ = disable_raw_mode();
+    }
+}
+
+/// Check whether the key is request to quit: `q`, `Esc` or `Ctrl+C`.
+pub fn is_quit(key: &Key) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => true,
+        KeyCode::Char('c') => key.modifiers.contains(Modifiers::CONTROL),
+        _ => false,
+    }
+}
+
+/// Center the lines in area of the given size.
+pub fn center(lines: &[String], width: usize, height: usize) -> Vec<String> {
+    let top = height.saturating_sub(lines.len()) / 2;
+    let mut res = vec![String::new(); top];
+    res.extend(lines.iter().map(|l| {
+        let pad = width.saturating_sub(l.chars().count()) / 2;
+        format!("{}{l}", " ".repeat(pad))
+    }));
+    res.truncate(height);
+    res
+}
@@ -0,0 +1,253 @@
+use std::{mem, time::Duration};
+
+use anyhow::Result;
+use termal::{
+    codes,
+    raw::events::{Key, KeyCode},
+};
+
+use crate::present::{
+    Action, Display, Live, Presenter, Slide,
+    term::{RawTerm, is_quit},
+};
+
+/// How often is the terminal checked for change of size and the
+/// presentation for changes done by other controllers.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+const HELP: &str = "←/→ slide  PgUp/PgDn song  <n>Enter slide  <n>s song  \
+    / verse  b blank  l language  q quit";
+
+/// Pending input typed by the operator.
+enum Input {
+    None,
+    /// Number typed before `Enter` or `s`.
+    Number(String),
+    /// Verse identifier typed after `/`.
+    Verse(String),
+}
+
+/// Interactive presentation controlled from the terminal. The displays are
+/// updated after every change, including changes done by other controllers.
+pub fn run_tui(live: &Live, displays: &mut [Box<dyn Display>]) -> Result<()> {
+    let mut term = RawTerm::new()?;
+    let mut input = Input::None;
+    let mut message = String::new();
+
+    let mut version = None;
+    let mut size = (0, 0);
+    let mut redraw = true;
+    loop {
+        let cur = live.version();
+        if version != Some(cur) {
+            version = Some(cur);
+            let errs = live.with(|p| update(p, displays));
+            if !errs.is_empty() {
+                message = errs;
+            }
+            redraw = true;
+        }
+
+        if redraw || size != term.size() {
+            size = term.size();
+            let lines = live.with(|p| render(p, &input, &message, size));
+            term.draw_lines(lines)?;
+        }
+
+        let Some(key) = term.read_key(POLL_INTERVAL)? else {
+            redraw = false;
+            continue;
+        };
+        redraw = true;
+        message.clear();
+
+        let action = match (&mut input, key.code) {
+            (Input::Verse(v), KeyCode::Enter) => {
+                let v = mem::take(v);
+                input = Input::None;
+                Some(Action::Verse(v))
+            }
+            (Input::Verse(_), KeyCode::Esc) => {
+                input = Input::None;
+                None
+            }
+            (Input::Verse(v), KeyCode::Backspace) => {
+                v.pop();
+                None
+            }
+            (Input::Verse(v), _) => {
+                v.extend(key.key_char.filter(|c| !c.is_control()));
+                None
+            }
+            (Input::Number(n), KeyCode::Enter) => {
+                let n = n.parse().unwrap_or(0usize);
+                input = Input::None;
+                Some(Action::Slide(n.saturating_sub(1)))
+            }
+            (Input::Number(n), KeyCode::Char('s')) => {
+                let n = n.parse().unwrap_or(0usize);
+                input = Input::None;
+                Some(Action::Song(n.saturating_sub(1)))
+            }
+            (Input::Number(_), KeyCode::Esc) => {
+                input = Input::None;
+                None
+            }
+            (Input::None, KeyCode::Char('/')) => {
+                input = Input::Verse(String::new());
+                None
+            }
+            (_, KeyCode::Char(c)) if c.is_ascii_digit() => {
+                match &mut input {
+                    Input::Number(n) => n.push(c),
+                    _ => input = Input::Number(c.to_string()),
+                }
+                None
+            }
+            _ if is_quit(&key) => return Ok(()),
+            _ => {
+                input = Input::None;
+                key_action(&key)
+            }
+        };
+
+        if let Some(a) = action
+            && !live.apply(a)
+        {
+            message = "There is no such slide.".into();
+        }
+    }
+}
+
+fn key_action(key: &Key) -> Option<Action> {
+    match key.code {
+        KeyCode::Right
+        | KeyCode::Down
+        | KeyCode::Space
+        | KeyCode::Char('j') => Some(Action::Next),
+        KeyCode::Left
+        | KeyCode::Up
+        | KeyCode::Backspace
+        | KeyCode::Char('k') => Some(Action::Prev),
+        KeyCode::PgDown | KeyCode::Char('n') => Some(Action::NextSong),
+        KeyCode::PgUp | KeyCode::Char('p') => Some(Action::PrevSong),
+        KeyCode::Home | KeyCode::Char('g') => Some(Action::Slide(0)),
+        KeyCode::Char('b' | '.') => Some(Action::ToggleBlank),
+        KeyCode::Char('l') => Some(Action::NextLanguage),
+        _ => None,
+    }
+}
+
+/// Update all the displays. Returns message describing the errors.
+fn update(p: &Presenter, displays: &mut [Box<dyn Display>]) -> String {
+    let errs: Vec<_> = displays
+        .iter_mut()
+        .filter_map(|d| d.update(p).err())
+        .map(|e| format!("error: {e}"))
+        .collect();
+    errs.join(" ")
+}
+
+fn render(
+    p: &Presenter,
+    input: &Input,
+    message: &str,
+    (width, height): (usize, usize),
+) -> Vec<String> {
+    let bold = |s: &str| format!("{}{s}{}", codes::BOLD, codes::RESET);
+    let rule = "─".repeat(width);
+    let mut res = vec![];
+
+    let songs = p.deck().songs.len();
+    let Some(conf) = p.conf() else {
+        res.push(bold("No songs to present."));
+        res.push(format!("{}q quit{}", codes::FAINT, codes::RESET));
+        return res;
+    };
+
+    let mut title = format!(
+        "Song {}/{songs}: {} ({})",
+        p.song() + 1,
+        conf.name,
+        conf.language
+    );
+    if p.is_blank() {
+        title += &format!("  {}BLANK{}", codes::INVERSE, codes::RESET);
+    }
+    res.push(bold(&title));
+    res.push(format!(
+        "{}{}{}",
+        codes::FAINT,
+        p.deck().songs[p.song()].path,
+        codes::RESET
+    ));
+    res.extend(conf.notes.iter().map(|n| note(n)));
+    res.push(rule.clone());
+
+    let slides = conf.slides.len();
+    let cur_label = format!("Current ({}/{slides})", p.slide() + 1);
+    slide_lines(&mut res, &cur_label, p.current());
+    res.push(String::new());
+    let next_label = if p.slide() + 1 < slides {
+        "Next".to_string()
+    } else {
+        "Next (next song)".to_string()
+    };
+    slide_lines(&mut res, &next_label, p.upcoming());
+
+    // Keep the help and input at the bottom.
+    let body = height.saturating_sub(3);
+    res.truncate(body);
+    res.resize(body, String::new());
+    res.push(rule);
+    res.push(format!("{}{HELP}{}", codes::FAINT, codes::RESET));
+    res.push(match input {
+        Input::None => message.to_string(),
+        Input::Number(n) => format!("> {n}"),
+        Input::Verse(v) => format!("/ {v}"),
+    });
+
+    for l in &mut res {
+        truncate(l, width);
+    }
+    res
+}
+
+fn slide_lines(res: &mut Vec<String>, title: &str, slide: Option<&Slide>) {
+    let Some(slide) = slide else {
+        res.push(format!("{title}: -"));
+        return;
+    };
+    res.push(format!(
+        "{title}: {}{}{}",
+        codes::BOLD,
+        slide.label,
+        codes::RESET
+    ));
+    res.extend(slide.lines.iter().map(|l| format!("    {l}")));
+    res.extend(slide.notes.iter().map(|n| format!("    {}", note(n))));
+}
+
+fn note(n: &str) -> String {
+    format!("{}Note: {n}{}", codes::CYAN_FG, codes::RESET)
+}
+
+/// Truncate line to the width. Escape codes are not counted.
+fn truncate(s: &mut String, width: usize) {
+    let mut visible = 0;
+    let mut esc = false;
+    for (i, c) in s.char_indices() {
+        if c == '\x1b' {
+            esc = true;
+        } else if esc {
+            esc = !c.is_ascii_alphabetic();
+        } else {
+            visible += 1;
+            if visible > width {
+                s.truncate(i);
+                s.push_str(codes::RESET);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{panic, thread};
+
+use anyhow::Result;
+
+use crate::{Song, out_fmt::OutFmt};
+
+/// Render the default configuration of each song to each of the outputs.
+/// Outputs are initialized before the first song and finalized after the
+/// last one.
+pub fn render<'a, O: OutFmt>(
+    songs: impl IntoIterator<Item = &'a Song>,
+    outputs: &mut [O],
+) -> Result<()> {
+    render_languages(songs, &[], outputs)
+}
+
+/// Same as [`render`], but each song is rendered in each of the languages
+/// that it has, see [`Song::configs_for`]. Each language is rendered as
+/// separate song.
+pub fn render_languages<'a, O: OutFmt>(
+    songs: impl IntoIterator<Item = &'a Song>,
+    languages: &[String],
+    outputs: &mut [O],
+) -> Result<()> {
+    for o in outputs.iter_mut() {
+        o.init()?;
+    }
+    let confs = songs.into_iter().flat_map(|s| {
+        s.configs_for(languages).into_iter().map(move |c| (s, c))
+    });
+    for (i, (s, cfg)) in confs.enumerate() {
+        for o in outputs.iter_mut() {
+            if i != 0 {
+                o.song_space()?;
+            }
+            o.begin_song(&cfg.name, &s.idents.get_name(cfg.language))?;
+            if !cfg.notes.is_empty() {
+                o.write_notes(&cfg.notes)?;
+            }
+            for (i, (id, v)) in cfg.order.iter().zip(&cfg.verses).enumerate() {
+                if i != 0 {
+                    o.verse_space()?;
+                }
+                o.write_labeled_verse(&s.idents.get_name(*id), v.as_slice())?;
+                let notes = &cfg.verse_notes[i];
+                if !notes.is_empty() {
+                    o.write_notes(notes)?;
+                }
+            }
+        }
+    }
+    for o in outputs.iter_mut() {
+        o.finalize()?;
+    }
+    Ok(())
+}
+
+/// Outputs that are rendered together from the same songs in the same
+/// languages. See [`render_parallel`].
+#[derive(Debug)]
+pub struct Batch<'a, O> {
+    pub songs: &'a [&'a Song],
+    /// Languages of the songs, see [`render_languages`].
+    pub languages: &'a [String],
+    pub outputs: Vec<O>,
+}
+
+/// Render each group of batches in its own thread. Batches in a group are
+/// rendered one after another. Outputs that share a writer must be in the
+/// same group, so that their output is not mixed. If rendering fails, the
+/// error of the first failed group is returned.
+pub fn render_parallel<O: OutFmt + Send>(
+    groups: &mut [Vec<Batch<'_, O>>],
+) -> Result<()> {
+    thread::scope(|s| {
+        let handles: Vec<_> = groups
+            .iter_mut()
+            .map(|g| {
+                s.spawn(|| {
+                    g.iter_mut().try_for_each(|b| {
+                        render_languages(
+                            b.songs.iter().copied(),
+                            b.languages,
+                            &mut b.outputs,
+                        )
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter().try_for_each(|h| {
+            h.join().unwrap_or_else(|e| panic::resume_unwind(e))
+        })
+    })
+}
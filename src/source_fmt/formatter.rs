@@ -0,0 +1,337 @@
+use crate::{
+    parse::Token,
+    source_fmt::{escape_line, escape_string},
+    syntax::{
+        Item, OrderEntry, Separated, SyntaxToken, SyntaxTree, TriviaKind,
+        decode_line_string, decode_string,
+    },
+};
+
+const INDENT: &str = "    ";
+
+/// Maximum width of lists that are written on single line.
+const WIDTH: usize = 79;
+
+pub struct Formatter<'a> {
+    tree: &'a SyntaxTree,
+    out: String,
+}
+
+impl<'a> Formatter<'a> {
+    pub fn new(tree: &'a SyntaxTree) -> Self {
+        Self {
+            tree,
+            out: String::new(),
+        }
+    }
+
+    pub fn format(mut self) -> String {
+        for item in &self.tree.items {
+            let newlines = self.comments(item.first(), "");
+            // Keep the empty lines that group the items. Languages are
+            // always separated.
+            if newlines > 1 || matches!(item, Item::Language { .. }) {
+                self.blank_line();
+            }
+
+            match item {
+                Item::Names { .. } => self.names(item),
+                Item::Name { assign, value, .. } => {
+                    self.out.push_str("name");
+                    self.token(assign, " ");
+                    self.token(value, " ");
+                }
+                Item::Order { .. } => self.order(item),
+                Item::Note { assign, value, .. } => {
+                    self.out.push_str("note");
+                    self.token(assign, " ");
+                    self.token(value, " ");
+                }
+                Item::Language { lang, .. } => {
+                    self.out.push_str("#language");
+                    self.token(lang, " ");
+                }
+                Item::Verse {
+                    ident,
+                    colon,
+                    terms,
+                    note,
+                } => {
+                    self.out.push_str(self.tree.text(ident));
+                    self.token(colon, "");
+                    self.terms(terms);
+                    if let Some(n) = note {
+                        self.token(&n.kw, " ");
+                        self.token(&n.value, " ");
+                    }
+                }
+            }
+            self.out.push('\n');
+        }
+
+        self.comments(&self.tree.eof, "");
+        self.out
+    }
+
+    fn names(&mut self, item: &Item) {
+        let Item::Names {
+            assign,
+            open,
+            entries,
+            close,
+            ..
+        } = item
+        else {
+            unreachable!();
+        };
+
+        let lines: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}: {}",
+                    self.tree.text(&e.lang),
+                    self.value(&e.value)
+                )
+            })
+            .collect();
+
+        let comments = [assign, open, close].iter().any(|t| t.has_comments())
+            || entries.iter().any(|e| {
+                [&e.lang, &e.colon, &e.value]
+                    .into_iter()
+                    .chain(&e.sep)
+                    .any(|t| t.has_comments())
+            });
+
+        self.out.push_str("name");
+        self.token(assign, " ");
+        let inner = entries.iter().map(|e| {
+            (
+                &e.lang,
+                [&e.colon, &e.value].into_iter().chain(&e.sep).collect(),
+            )
+        });
+        self.list(open, inner, lines, close, comments, "{", "}");
+    }
+
+    fn order(&mut self, item: &Item) {
+        let Item::Order {
+            assign,
+            open,
+            entries,
+            close,
+            ..
+        } = item
+        else {
+            unreachable!();
+        };
+
+        let lines: Vec<_> = entries
+            .iter()
+            .map(|e| match &e.note {
+                Some(n) => format!(
+                    "{} note {}",
+                    self.tree.text(&e.tok),
+                    self.value(&n.value)
+                ),
+                None => self.tree.text(&e.tok).to_string(),
+            })
+            .collect();
+        // Tokens after the identifier.
+        fn rest(e: &OrderEntry) -> Vec<&SyntaxToken> {
+            e.note
+                .iter()
+                .flat_map(|n| [&n.kw, &n.value])
+                .chain(&e.sep)
+                .collect()
+        }
+        let comments = [assign, open, close].iter().any(|t| t.has_comments())
+            || entries.iter().any(|e| {
+                e.tok.has_comments()
+                    || rest(e).iter().any(|t| t.has_comments())
+            });
+
+        self.out.push_str("order");
+        self.token(assign, " ");
+        let inner = entries.iter().map(|e| (&e.tok, rest(e)));
+        self.list(open, inner, lines, close, comments, "[", "]");
+    }
+
+    /// Write list of entries. The list is written on single line if it
+    /// fits and if there are no comments.
+    #[allow(clippy::too_many_arguments)]
+    fn list<'t>(
+        &mut self,
+        open: &SyntaxToken,
+        entries: impl Iterator<Item = (&'t SyntaxToken, Vec<&'t SyntaxToken>)>,
+        lines: Vec<String>,
+        close: &SyntaxToken,
+        comments: bool,
+        open_str: &str,
+        close_str: &str,
+    ) {
+        let line_start = self.out.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        // Curly brackets have spaces inside.
+        let pad = if open_str == "{" && !lines.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        let single =
+            format!(" {open_str}{pad}{}{pad}{close_str}", lines.join(", "));
+
+        if !comments && self.out.len() - line_start + single.len() <= WIDTH {
+            self.out.push_str(&single);
+            return;
+        }
+
+        self.token(open, " ");
+        self.out.push('\n');
+        for ((first, rest), line) in entries.zip(lines) {
+            self.comments(first, INDENT);
+            for t in rest {
+                self.own_line_comments(t, INDENT);
+            }
+            self.out.push_str(INDENT);
+            self.out.push_str(&line);
+            self.out.push_str(",\n");
+        }
+        self.comments(close, INDENT);
+        self.out.push_str(close_str);
+    }
+
+    fn terms(&mut self, terms: &[Separated]) {
+        for t in terms {
+            self.inline_comments(&t.tok);
+            self.space(" ");
+            match t.tok.kind {
+                Token::LineString => self.line_string(&t.tok),
+                _ => self.out.push_str(self.tree.text(&t.tok)),
+            }
+            if let Some(sep) = &t.sep {
+                self.token(sep, " ");
+            }
+        }
+    }
+
+    fn line_string(&mut self, tok: &SyntaxToken) {
+        let lines = decode_line_string(self.tree.text(tok));
+        if lines.is_empty() {
+            self.out.push_str("l{}");
+            return;
+        }
+
+        self.out.push_str("l{\n");
+        for l in lines {
+            self.out.push_str(INDENT);
+            self.out.push_str(&escape_line(&l));
+            self.out.push('\n');
+        }
+        self.out.push('}');
+    }
+
+    fn value(&self, tok: &SyntaxToken) -> String {
+        let value = decode_string(self.tree.text(tok));
+        format!("\"{}\"", escape_string(&value))
+    }
+
+    /// Write token that is on the same line as the previous one.
+    fn token(&mut self, tok: &SyntaxToken, space: &str) {
+        self.inline_comments(tok);
+        self.space(space);
+        match tok.kind {
+            Token::String => {
+                let value = self.value(tok);
+                self.out.push_str(&value);
+            }
+            _ => self.out.push_str(self.tree.text(tok)),
+        }
+    }
+
+    /// Write the separating space or indentation if this is start of line.
+    fn space(&mut self, space: &str) {
+        if self.at_line_start() {
+            self.out.push_str(INDENT);
+        } else {
+            self.out.push_str(space);
+        }
+    }
+
+    /// Write comments that precede token in the middle of line. Comments
+    /// that are on the same line as the previous token are kept there.
+    fn inline_comments(&mut self, tok: &SyntaxToken) {
+        let mut newline = false;
+        for t in &tok.leading {
+            match t.kind {
+                TriviaKind::Newline => newline = true,
+                TriviaKind::Comment => {
+                    let comment = t.span.text(&self.tree.src);
+                    if !newline && !self.at_line_start() {
+                        self.out.push(' ');
+                    } else {
+                        if !self.at_line_start() {
+                            self.out.push('\n');
+                        }
+                        self.out.push_str(INDENT);
+                    }
+                    self.out.push_str(comment);
+                    self.out.push('\n');
+                    newline = false;
+                }
+                TriviaKind::Space => {}
+            }
+        }
+    }
+
+    /// Write all the comments that precede the token on separate lines.
+    fn own_line_comments(&mut self, tok: &SyntaxToken, indent: &str) {
+        for t in &tok.leading {
+            if t.kind == TriviaKind::Comment {
+                self.out.push_str(indent);
+                self.out.push_str(t.span.text(&self.tree.src));
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// Write the comments that precede token at the start of line. Comment
+    /// that was on the same line as the previous token stays at the end of
+    /// the previous line. Returns the number of new lines between the last
+    /// comment and the token.
+    fn comments(&mut self, tok: &SyntaxToken, indent: &str) -> usize {
+        let mut newlines = 0;
+        for t in &tok.leading {
+            match t.kind {
+                TriviaKind::Newline => newlines += 1,
+                TriviaKind::Comment => {
+                    let comment = t.span.text(&self.tree.src);
+                    if newlines == 0 && self.out.ends_with('\n') {
+                        self.out.pop();
+                        self.out.push(' ');
+                    } else {
+                        if newlines > 1 {
+                            self.blank_line();
+                        }
+                        self.out.push_str(indent);
+                    }
+                    self.out.push_str(comment);
+                    self.out.push('\n');
+                    newlines = 0;
+                }
+                TriviaKind::Space => {}
+            }
+        }
+        newlines
+    }
+
+    fn blank_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+}
@@ -0,0 +1,67 @@
+//! Formatting of wohu sources.
+
+mod formatter;
+
+use anyhow::Result;
+
+use self::formatter::Formatter;
+
+use crate::{parse::is_space, syntax::parse_syntax};
+
+/// Reformat wohu source into its canonical form. Comments and empty lines
+/// that separate groups of items are preserved.
+pub fn format_str(src: &str) -> Result<String> {
+    let tree = parse_syntax(src)?;
+    Ok(Formatter::new(&tree).format())
+}
+
+/// Escape string so that it may be used inside `"..."`.
+pub fn escape_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '"' | '\\') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Escape single line so that it may be used inside `l{...}`. Whitespace at
+/// the start and end of the line is escaped so that it is not trimmed.
+pub fn escape_line(s: &str) -> String {
+    let last = s.chars().count().saturating_sub(1);
+    let mut res = String::with_capacity(s.len());
+    for (i, c) in s.chars().enumerate() {
+        let edge = (i == 0 || i == last) && is_space(c);
+        if matches!(c, '}' | '\\' | '\n') || edge {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    #[test]
+    fn idempotent() {
+        let src = "// comment\n\
+            name = { cs: \"Píseň \\\"a\\\"\", en: \"Song\" }\n\
+            v1: l{\n    Hospodin   je\tmůj  pastýř\n  a\\ b \\} c\\\\d\n}\n\
+            v-1: l{ one line } + v1\n\n\n\
+            order = [v1 note \"first\", v-1, refrén]\n\
+            refrén: l{ top } // trailing\n\
+            #language cs\n\
+            refrén: l{\n Sláva  \n}\n\
+            #language en\n\
+            refrén: l{glory}\n";
+        let formatted = format_str(src).unwrap();
+        assert_ne!(formatted, src);
+        assert_eq!(format_str(&formatted).unwrap(), formatted);
+        assert!(parse_str(&formatted).is_ok());
+    }
+}
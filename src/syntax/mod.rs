@@ -0,0 +1,21 @@
+//! Lossless syntax tree of wohu sources for the tools that work with the
+//! source, such as the linter and the formatter.
+
+mod span;
+mod syntax_error;
+mod syntax_lexer;
+mod syntax_parser;
+mod syntax_token;
+mod tree;
+
+use anyhow::Result;
+
+pub use self::{span::*, syntax_error::*, syntax_token::*, tree::*};
+pub use crate::parse::{Token, is_ident_continue, is_ident_start};
+
+pub(crate) use self::{syntax_lexer::*, syntax_parser::*};
+
+/// Parse the source into lossless syntax tree.
+pub fn parse_syntax(src: &str) -> Result<SyntaxTree> {
+    SyntaxParser::new(src)?.parse()
+}
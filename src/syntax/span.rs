@@ -0,0 +1,63 @@
+/// Range of bytes in the source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Position in the source. Both line and column start at 1. Column is
+/// counted in characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pos {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn text(self, src: &str) -> &str {
+        &src[self.start..self.end]
+    }
+
+    pub fn pos(self, src: &str) -> Pos {
+        Pos::at(src, self.start)
+    }
+}
+
+impl Pos {
+    /// Get position of the byte at the given offset.
+    pub fn at(src: &str, offset: usize) -> Self {
+        let before = &src[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let col = before[line_start..].chars().count() + 1;
+        Self { line, col }
+    }
+
+    /// Get offset of the byte at this position. Positions out of the source
+    /// are clamped to its end.
+    pub fn offset(self, src: &str) -> usize {
+        let line_start = src
+            .split_inclusive('\n')
+            .take(self.line.saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>();
+        let line = src[line_start..].split_inclusive('\n').next();
+        let line_end = line_start + line.map_or(0, str::len);
+        line.unwrap_or_default()
+            .char_indices()
+            .map(|(i, _)| line_start + i)
+            .chain([line_end])
+            .nth(self.col.saturating_sub(1))
+            .unwrap_or(line_end)
+    }
+}
+
+impl std::fmt::Display for Pos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
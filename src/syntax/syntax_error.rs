@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+use crate::syntax::{Pos, Span};
+
+/// Error in the syntax of wohu source.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub span: Span,
+    pub pos: Pos,
+    pub msg: String,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pos, self.msg)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+
+use crate::{
+    parse::{Token, is_ident_continue, is_ident_start, is_space},
+    syntax::{Pos, Span, SyntaxError, SyntaxToken, Trivia, TriviaKind},
+};
+
+/// Lexer that doesn't lose any information about the source.
+pub struct SyntaxLexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> SyntaxLexer<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    pub fn next(&mut self) -> Result<SyntaxToken> {
+        let leading = self.trivia();
+        let start = self.pos;
+
+        let kind = match self.cur() {
+            None => Token::Eof,
+            Some(c) if is_ident_start(c) => self.alnum()?,
+            Some('"') => self.string()?,
+            Some('#') => self.declare()?,
+            Some(c) => {
+                self.pos += c.len_utf8();
+                match c {
+                    ':' => Token::Colon,
+                    '=' => Token::Assign,
+                    '+' => Token::Add,
+                    ',' => Token::Comma,
+                    '{' => Token::OpenBracket,
+                    '}' => Token::CloseBracket,
+                    '[' => Token::OpenSq,
+                    ']' => Token::CloseSq,
+                    c => self.err(
+                        start,
+                        format!("Unknown operator or punctuation `{c}`."),
+                    )?,
+                }
+            }
+        };
+
+        Ok(SyntaxToken {
+            kind,
+            span: Span::new(start, self.pos),
+            leading,
+        })
+    }
+
+    fn trivia(&mut self) -> Vec<Trivia> {
+        let mut res = vec![];
+        loop {
+            let start = self.pos;
+            let kind = match self.cur() {
+                Some('\n') => {
+                    self.pos += 1;
+                    TriviaKind::Newline
+                }
+                Some(c) if is_space(c) => {
+                    self.skip_while(|c| c != '\n' && is_space(c));
+                    TriviaKind::Space
+                }
+                Some('/') if self.rest().starts_with("//") => {
+                    self.skip_while(|c| c != '\n');
+                    TriviaKind::Comment
+                }
+                _ => return res,
+            };
+            res.push(Trivia {
+                kind,
+                span: Span::new(start, self.pos),
+            });
+        }
+    }
+
+    fn alnum(&mut self) -> Result<Token> {
+        let start = self.pos;
+        self.skip_while(is_ident_continue);
+
+        match &self.src[start..self.pos] {
+            "name" => return Ok(Token::KwName),
+            "order" => return Ok(Token::KwOrder),
+            "note" => return Ok(Token::KwNote),
+            _ => {}
+        }
+
+        if self.cur() != Some('{') {
+            return Ok(Token::Ident);
+        }
+
+        match &self.src[start..self.pos] {
+            "l" => {
+                self.pos += 1; // {
+                self.until_unescaped('}', start)?;
+                Ok(Token::LineString)
+            }
+            s => {
+                self.err(start, format!("Unknonw special string type `{s}`."))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<Token> {
+        let start = self.pos;
+        self.pos += 1; // "
+        self.until_unescaped('"', start)?;
+        Ok(Token::String)
+    }
+
+    fn declare(&mut self) -> Result<Token> {
+        let start = self.pos;
+        self.pos += 1; // #
+        self.skip_while(is_ident_continue);
+        match &self.src[start + 1..self.pos] {
+            "language" => Ok(Token::DecLanguage),
+            s => self.err(start, format!("Unknown declaration `{s}`")),
+        }
+    }
+
+    /// Move after the first unescaped `end`.
+    fn until_unescaped(&mut self, end: char, start: usize) -> Result<()> {
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == end {
+                self.pos += i + 1;
+                return Ok(());
+            }
+            if c == '\\' {
+                chars.next();
+            }
+        }
+        self.err(start, format!("Missing closing '{end}'."))
+    }
+
+    fn skip_while(&mut self, f: impl Fn(char) -> bool) {
+        let len = self.rest().find(|c| !f(c)).unwrap_or(self.rest().len());
+        self.pos += len;
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn cur(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn err<T>(&self, start: usize, msg: String) -> Result<T> {
+        Err(SyntaxError {
+            span: Span::new(start, self.pos.max(start)),
+            pos: Pos::at(self.src, start),
+            msg,
+        }
+        .into())
+    }
+}
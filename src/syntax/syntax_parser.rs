@@ -0,0 +1,177 @@
+use anyhow::Result;
+
+use crate::{
+    parse::Token,
+    syntax::{
+        Item, NameEntry, Note, OrderEntry, Separated, SyntaxError,
+        SyntaxLexer, SyntaxToken, SyntaxTree,
+    },
+};
+
+pub struct SyntaxParser<'a> {
+    src: &'a str,
+    lex: SyntaxLexer<'a>,
+    cur: SyntaxToken,
+}
+
+impl<'a> SyntaxParser<'a> {
+    pub fn new(src: &'a str) -> Result<Self> {
+        let mut lex = SyntaxLexer::new(src);
+        let cur = lex.next()?;
+        Ok(Self { src, lex, cur })
+    }
+
+    pub fn parse(mut self) -> Result<SyntaxTree> {
+        let mut items = vec![];
+        loop {
+            let item = match self.cur.kind {
+                Token::KwName => self.name()?,
+                Token::KwOrder => self.order()?,
+                Token::KwNote => Item::Note {
+                    kw: self.take()?,
+                    assign: self.expect(Token::Assign)?,
+                    value: self.expect(Token::String)?,
+                },
+                Token::DecLanguage => Item::Language {
+                    decl: self.take()?,
+                    lang: self.expect(Token::Ident)?,
+                },
+                Token::Ident => self.verse()?,
+                Token::Eof => break,
+                t => self.err(format!("Unexpected token `{t:?}`."))?,
+            };
+            items.push(item);
+        }
+
+        Ok(SyntaxTree {
+            src: self.src.to_string(),
+            items,
+            eof: self.cur,
+        })
+    }
+
+    fn name(&mut self) -> Result<Item> {
+        let kw = self.take()?;
+        let assign = self.expect(Token::Assign)?;
+        if self.cur.kind == Token::String {
+            return Ok(Item::Name {
+                kw,
+                assign,
+                value: self.take()?,
+            });
+        }
+
+        let open = self.expect(Token::OpenBracket)?;
+        let mut entries = vec![];
+        while self.cur.kind != Token::CloseBracket {
+            entries.push(NameEntry {
+                lang: self.expect(Token::Ident)?,
+                colon: self.expect(Token::Colon)?,
+                value: self.expect(Token::String)?,
+                sep: self.take_if(Token::Comma)?,
+            });
+        }
+
+        Ok(Item::Names {
+            kw,
+            assign,
+            open,
+            entries,
+            close: self.take()?,
+        })
+    }
+
+    fn order(&mut self) -> Result<Item> {
+        let kw = self.take()?;
+        let assign = self.expect(Token::Assign)?;
+        let open = self.expect(Token::OpenSq)?;
+        let mut entries = vec![];
+        while self.cur.kind != Token::CloseSq {
+            entries.push(OrderEntry {
+                tok: self.expect(Token::Ident)?,
+                note: self.note()?,
+                sep: self.take_if(Token::Comma)?,
+            });
+        }
+
+        Ok(Item::Order {
+            kw,
+            assign,
+            open,
+            entries,
+            close: self.take()?,
+        })
+    }
+
+    fn verse(&mut self) -> Result<Item> {
+        let ident = self.take()?;
+        let colon = self.expect(Token::Colon)?;
+        let mut terms = vec![];
+        loop {
+            if !matches!(self.cur.kind, Token::LineString | Token::Ident) {
+                self.err(format!(
+                    "Expected line string or identifier but found `{:?}`.",
+                    self.cur.kind
+                ))?;
+            }
+            let tok = self.take()?;
+            let sep = self.take_if(Token::Add)?;
+            let end = sep.is_none();
+            terms.push(Separated { tok, sep });
+            if end {
+                break;
+            }
+        }
+
+        Ok(Item::Verse {
+            ident,
+            colon,
+            terms,
+            note: self.note()?,
+        })
+    }
+
+    /// Parse note attached to the previous verse or order entry.
+    fn note(&mut self) -> Result<Option<Note>> {
+        if self.cur.kind != Token::KwNote {
+            return Ok(None);
+        }
+        Ok(Some(Note {
+            kw: self.take()?,
+            value: self.expect(Token::String)?,
+        }))
+    }
+
+    fn take_if(&mut self, t: Token) -> Result<Option<SyntaxToken>> {
+        if self.cur.kind == t {
+            self.take().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn expect(&mut self, t: Token) -> Result<SyntaxToken> {
+        if self.cur.kind != t {
+            self.err(format!(
+                "Expected the token `{t:?}` but found `{:?}`",
+                self.cur.kind
+            ))?;
+        }
+        self.take()
+    }
+
+    /// Take the current token and move to the next one.
+    fn take(&mut self) -> Result<SyntaxToken> {
+        let next = self.lex.next()?;
+        Ok(std::mem::replace(&mut self.cur, next))
+    }
+
+    fn err<T>(&self, msg: String) -> Result<T> {
+        Err(SyntaxError {
+            span: self.cur.span,
+            pos: self.cur.span.pos(self.src),
+            msg,
+        }
+        .into())
+    }
+}
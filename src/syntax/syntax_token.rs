@@ -0,0 +1,31 @@
+use crate::{parse::Token, syntax::Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// Whitespace without new lines.
+    Space,
+    Newline,
+    /// Line comment starting with `//`. The span doesn't contain the new
+    /// line.
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+/// Token together with all the trivia that precedes it.
+#[derive(Debug, Clone)]
+pub struct SyntaxToken {
+    pub kind: Token,
+    pub span: Span,
+    pub leading: Vec<Trivia>,
+}
+
+impl SyntaxToken {
+    pub fn has_comments(&self) -> bool {
+        self.leading.iter().any(|t| t.kind == TriviaKind::Comment)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    parse::{is_space, join_space},
+    syntax::SyntaxToken,
+};
+
+/// Lossless syntax tree of a wohu source. Each token contains the trivia
+/// that precedes it so the original source can be reconstructed.
+#[derive(Debug)]
+pub struct SyntaxTree {
+    pub src: String,
+    pub items: Vec<Item>,
+    /// End of file. Its trivia contains the trailing comments.
+    pub eof: SyntaxToken,
+}
+
+#[derive(Debug)]
+pub enum Item {
+    /// `name = { lang: "name", ... }`
+    Names {
+        kw: SyntaxToken,
+        assign: SyntaxToken,
+        open: SyntaxToken,
+        entries: Vec<NameEntry>,
+        close: SyntaxToken,
+    },
+    /// `name = "name"`
+    Name {
+        kw: SyntaxToken,
+        assign: SyntaxToken,
+        value: SyntaxToken,
+    },
+    /// `order = [ident, ...]`
+    Order {
+        kw: SyntaxToken,
+        assign: SyntaxToken,
+        open: SyntaxToken,
+        entries: Vec<OrderEntry>,
+        close: SyntaxToken,
+    },
+    /// `note = "note"`
+    Note {
+        kw: SyntaxToken,
+        assign: SyntaxToken,
+        value: SyntaxToken,
+    },
+    /// `#language lang`
+    Language {
+        decl: SyntaxToken,
+        lang: SyntaxToken,
+    },
+    /// `ident: term + ...`
+    Verse {
+        ident: SyntaxToken,
+        colon: SyntaxToken,
+        /// Identifiers and line strings separated by `+`.
+        terms: Vec<Separated>,
+        note: Option<Note>,
+    },
+}
+
+/// `lang: "name"` optionally followed by `,`.
+#[derive(Debug)]
+pub struct NameEntry {
+    pub lang: SyntaxToken,
+    pub colon: SyntaxToken,
+    pub value: SyntaxToken,
+    pub sep: Option<SyntaxToken>,
+}
+
+/// `ident` optionally followed by note and `,`.
+#[derive(Debug)]
+pub struct OrderEntry {
+    pub tok: SyntaxToken,
+    pub note: Option<Note>,
+    pub sep: Option<SyntaxToken>,
+}
+
+/// `note "note"` attached to verse or order entry.
+#[derive(Debug)]
+pub struct Note {
+    pub kw: SyntaxToken,
+    pub value: SyntaxToken,
+}
+
+/// Token optionally followed by separator.
+#[derive(Debug)]
+pub struct Separated {
+    pub tok: SyntaxToken,
+    pub sep: Option<SyntaxToken>,
+}
+
+impl Item {
+    pub fn first(&self) -> &SyntaxToken {
+        match self {
+            Self::Names { kw, .. }
+            | Self::Name { kw, .. }
+            | Self::Order { kw, .. }
+            | Self::Note { kw, .. } => kw,
+            Self::Language { decl, .. } => decl,
+            Self::Verse { ident, .. } => ident,
+        }
+    }
+}
+
+impl SyntaxTree {
+    pub fn text(&self, tok: &SyntaxToken) -> &str {
+        tok.span.text(&self.src)
+    }
+}
+
+/// Get the value of string token `"..."`.
+pub fn decode_string(text: &str) -> String {
+    let text = text.strip_prefix('"').unwrap_or(text);
+    let text = text.strip_suffix('"').unwrap_or(text);
+
+    let mut res = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            res.extend(chars.next());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Get the lines of line string token `l{...}`. Whitespace is normalized in
+/// the same way as when parsing.
+pub fn decode_line_string(text: &str) -> Vec<String> {
+    let text = text.strip_prefix("l{").unwrap_or(text);
+    let text = text.strip_suffix('}').unwrap_or(text);
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut space = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                space = None;
+                continue;
+            }
+            c if is_space(c) => {
+                space = Some(join_space(space, c));
+                continue;
+            }
+            _ => {}
+        }
+
+        if let Some(s) = space.take()
+            && !line.is_empty()
+        {
+            line.push(s);
+        }
+        if c == '\\' {
+            line.extend(chars.next());
+        } else {
+            line.push(c);
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,246 @@
+//! Language specific typography of the lyrics.
+//!
+//! Rules are selected by the identifier of the `#language`. Non-breaking
+//! spaces are inserted as `U+00A0`, output formats write them in their own
+//! way (e.g. `~` in LaTeX). Normalization of quotes, apostrophes, dashes and
+//! ellipses is optional, see [`normalize_song`].
+
+use crate::{Line, Song};
+
+/// Non-breaking space.
+pub const NBSP: char = '\u{a0}';
+
+/// Typographic rules for one language.
+#[derive(Debug)]
+pub struct LangRules {
+    /// Primary language subtags that use the rules. Compared without case.
+    pub langs: &'static [&'static str],
+    /// Words that must not be at the end of line. The space after them is
+    /// replaced with non-breaking space. Compared without case.
+    pub nbsp_after: &'static [&'static str],
+    /// Opening and closing double quotes.
+    pub quotes: [char; 2],
+    /// Opening and closing single quotes.
+    pub single_quotes: [char; 2],
+    /// Dash between words. It is surrounded by spaces.
+    pub dash: char,
+}
+
+/// Characters that may precede word without separating it.
+const OPENING: &[char] = &['(', '[', '"', '\'', '„', '‚', '“', '‘', '»', '«'];
+
+/// Apostrophe used by all the languages.
+const APOSTROPHE: char = '’';
+
+/// Rules for all the supported languages.
+pub const LANG_RULES: &[LangRules] = &[
+    LangRules {
+        langs: &["cs"],
+        nbsp_after: &["k", "s", "v", "z", "o", "u", "a", "i"],
+        quotes: ['„', '“'],
+        single_quotes: ['‚', '‘'],
+        dash: '–',
+    },
+    LangRules {
+        langs: &["sk"],
+        nbsp_after: &["k", "s", "v", "z", "o", "u", "a", "i"],
+        quotes: ['„', '“'],
+        single_quotes: ['‚', '‘'],
+        dash: '–',
+    },
+    LangRules {
+        langs: &["de"],
+        nbsp_after: &[],
+        quotes: ['„', '“'],
+        single_quotes: ['‚', '‘'],
+        dash: '–',
+    },
+    LangRules {
+        langs: &["en"],
+        nbsp_after: &[],
+        quotes: ['“', '”'],
+        single_quotes: ['‘', '’'],
+        dash: '—',
+    },
+];
+
+/// Get the rules for the language identifier. Language tags with region or
+/// script (e.g. `cs-CZ`) use the rules of their primary language. Returns
+/// [`None`] if there are no rules for the language.
+pub fn rules_for(lang: &str) -> Option<&'static LangRules> {
+    let find = |lang: &str| {
+        LANG_RULES
+            .iter()
+            .find(|r| r.langs.iter().any(|l| l.eq_ignore_ascii_case(lang)))
+    };
+    find(lang).or_else(|| find(lang.split('-').next()?))
+}
+
+impl LangRules {
+    /// Apply the rules to single line.
+    pub fn apply(&self, line: &str) -> String {
+        let mut res = String::with_capacity(line.len());
+        let mut words = line.split(' ').peekable();
+        while let Some(w) = words.next() {
+            res += w;
+            if words.peek().is_none() {
+                break;
+            }
+            let bare = w.trim_start_matches(OPENING);
+            let glue = !bare.is_empty()
+                && self
+                    .nbsp_after
+                    .iter()
+                    .any(|n| n.eq_ignore_ascii_case(bare));
+            res.push(if glue { NBSP } else { ' ' });
+        }
+        res
+    }
+
+    /// Convert quotes, apostrophes, dashes and ellipses in the line to the
+    /// typographic forms of the language. Quotes are opening at the start of
+    /// word and closing elsewhere.
+    pub fn normalize(&self, line: &str) -> String {
+        let line = line.replace("...", "…").replace(" -- ", " - ");
+        let mut res = String::with_capacity(line.len());
+        let mut chars = line.chars().peekable();
+        let mut single_open = false;
+        while let Some(c) = chars.next() {
+            let prev = res.chars().next_back();
+            let next = chars.peek().copied();
+            let opening = prev.is_none_or(|p| {
+                p.is_whitespace()
+                    || matches!(p, '(' | '[')
+                    || p == self.quotes[0]
+                    || p == self.single_quotes[0]
+            });
+            let letter =
+                |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+            match c {
+                '"' | '„' | '“' | '”' | '«' | '»' => {
+                    res.push(self.quotes[usize::from(!opening)])
+                }
+                '\'' | '‚' | '‘' | '’' => {
+                    if (letter(prev) && letter(next))
+                        || (!opening && !single_open)
+                    {
+                        res.push(APOSTROPHE);
+                    } else {
+                        single_open = opening;
+                        res.push(self.single_quotes[usize::from(!opening)]);
+                    }
+                }
+                '-' | '–' | '—'
+                    if prev.is_none_or(|p| p == ' ')
+                        && next.is_none_or(|n| n == ' ') =>
+                {
+                    res.push(self.dash)
+                }
+                c => res.push(c),
+            }
+        }
+        res
+    }
+
+    /// Apply the rules to all lines of verse.
+    pub fn apply_verse(&self, verse: &[Line]) -> Vec<String> {
+        verse.iter().map(|l| self.apply(l)).collect()
+    }
+}
+
+/// Apply the rules of the language to the verse. Verse is unchanged if
+/// there are no rules for the language.
+pub fn apply_verse(lang: Option<&LangRules>, verse: &[Line]) -> Vec<String> {
+    match lang {
+        Some(r) => r.apply_verse(verse),
+        None => verse.iter().map(|l| l.to_string()).collect(),
+    }
+}
+
+/// Normalize quotes, apostrophes, dashes and ellipses in the names and verses
+/// of all the configurations of the song. Configurations of languages without
+/// rules are unchanged. Notes are not changed, they are only for the
+/// operator.
+pub fn normalize_song(song: &mut Song) {
+    for conf in song.configs.values_mut() {
+        let Some(rules) = rules_for(&song.idents.get_name(conf.language))
+        else {
+            continue;
+        };
+        conf.name = rules.normalize(&conf.name);
+        for l in conf.verses.iter_mut().flatten() {
+            *l = rules.normalize(l).into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_by_tag() {
+        let langs = |l| rules_for(l).map(|r| r.langs[0]);
+        assert_eq!(langs("cs"), Some("cs"));
+        assert_eq!(langs("CS"), Some("cs"));
+        assert_eq!(langs("cs-CZ"), Some("cs"));
+        assert_eq!(langs("sk"), Some("sk"));
+        assert_eq!(langs("de-AT-1996"), Some("de"));
+        assert_eq!(langs("en-GB"), Some("en"));
+        assert_eq!(langs("fr"), None);
+        assert_eq!(langs("czech"), None);
+        assert_eq!(langs("css"), None);
+    }
+
+    #[test]
+    fn nbsp_after_prepositions() {
+        let cs = rules_for("cs").unwrap();
+        assert_eq!(
+            cs.apply("jdu k vodám a (s tebou) V lese"),
+            "jdu k\u{a0}vodám a\u{a0}(s\u{a0}tebou) V\u{a0}lese"
+        );
+        // The last word of the line has nothing to join with.
+        assert_eq!(cs.apply("ty a"), "ty a");
+        assert_eq!(cs.apply("\"v\" domě"), "\"v\" domě");
+        let en = rules_for("en").unwrap();
+        assert_eq!(en.apply("a song"), "a song");
+        assert_eq!(apply_verse(None, &["k vodám".into()]), ["k vodám"]);
+    }
+
+    #[test]
+    fn smart_quotes() {
+        let cs = rules_for("cs").unwrap();
+        assert_eq!(
+            cs.normalize("Řekl \"ahoj\" a 'tak' -- konec..."),
+            "Řekl „ahoj“ a ‚tak‘ – konec…"
+        );
+        assert_eq!(cs.normalize("«už» (\"zas\")"), "„už“ („zas“)");
+        let en = rules_for("en").unwrap();
+        assert_eq!(
+            en.normalize("don't say \"it's 'fine'\" - ok"),
+            "don’t say “it’s ‘fine’” — ok"
+        );
+        // Apostrophe after word is not closing quote.
+        assert_eq!(en.normalize("rock 'n' rollin'"), "rock ‘n’ rollin’");
+        // Hyphens inside words stay.
+        assert_eq!(en.normalize("well-known"), "well-known");
+    }
+
+    #[test]
+    fn normalizes_song() {
+        let mut song = crate::parse_str(
+            "name = { cs: \"\\\"Píseň\\\"\", en: \"Song\" }\n\
+            v1: l{ \"a\" }\n\
+            #language cs\norder = [v1]\n\
+            #language en\norder = [v1]\n",
+        )
+        .unwrap();
+        normalize_song(&mut song);
+        let confs: Vec<_> = song
+            .ordered_configs()
+            .into_iter()
+            .map(|c| (c.name.as_str(), c.verses[0][0].as_ref()))
+            .collect();
+        assert_eq!(confs, [("„Píseň“", "„a“"), ("Song", "“a”")]);
+    }
+}